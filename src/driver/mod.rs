@@ -0,0 +1,199 @@
+use crate::{
+    error::{Error, ParseError},
+    llvm::{grammar::ModuleParser, Module},
+};
+use std::io::{Read, Write};
+
+#[cfg(test)]
+pub mod test;
+
+pub const USAGE: &str = "usage: silly-compiler <command> [options] [file...]
+
+reads each file (or stdin if there aren't any, or if a file is `-`) as an LLVM assembly module
+
+commands:
+    parse     check that the input parses
+    verify    check that the input parses and is valid
+    print     print the input back out as LLVM assembly
+    dump      print the parsed syntax tree of the input
+    passes    run the passes given with `--passes` over the input, then print it
+    emit      verify the input and emit it for the target given with `--target`
+    help      print this message
+
+options:
+    -o, --output <file>     write output to a file instead of stdout
+    -p, --passes <passes>   a comma separated list of passes to run. available passes are: verify
+    -t, --target <target>   the target to emit code for. available targets are: llvm (the default)
+    -h, --help              print this message
+";
+
+/// what to do with each input
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Command {
+    Parse,
+    Verify,
+    Print,
+    Dump,
+    Passes,
+    Emit,
+    Help,
+}
+
+/// something that can be run over a module with the `passes` command
+#[derive(Debug)]
+pub struct Pass {
+    pub name: &'static str,
+    /// runs the pass over a module. the file name is used for error messages
+    pub run: fn(&mut Module, &str) -> Result<(), Error>,
+}
+
+pub const PASSES: &[Pass] = &[Pass {
+    name: "verify",
+    run: |module, file_name| verify(module, file_name),
+}];
+
+/// what the `emit` command can produce
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Target {
+    /// LLVM assembly
+    #[default]
+    Llvm,
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub command: Command,
+    /// the files to read input from, where `-` is stdin
+    pub inputs: Vec<String>,
+    /// the file to write output to. if this isn't specified, output goes to stdout
+    pub output: Option<String>,
+    pub passes: Vec<&'static Pass>,
+    pub target: Target,
+}
+
+impl Options {
+    /// parses command line arguments, not including the name of the program
+    pub fn from_arguments(arguments: impl IntoIterator<Item = String>) -> Result<Self, Error> {
+        let mut arguments = arguments.into_iter();
+        let mut command = None;
+        let mut options = Self {
+            command: Command::Help,
+            inputs: Vec::new(),
+            output: None,
+            passes: Vec::new(),
+            target: Target::default(),
+        };
+
+        while let Some(argument) = arguments.next() {
+            let mut value = |name: &str| arguments.next().ok_or_else(|| Error::Usage(format!("{name} needs a value")));
+
+            match argument.as_str() {
+                "-h" | "--help" => command = Some(Command::Help),
+                "-o" | "--output" => options.output = Some(value(&argument)?),
+                "-p" | "--passes" => {
+                    for name in value(&argument)?.split(',').filter(|name| !name.is_empty()) {
+                        let pass = PASSES.iter().find(|pass| pass.name == name).ok_or_else(|| Error::Usage(format!("unknown pass `{name}`")))?;
+                        options.passes.push(pass);
+                    }
+                }
+                "-t" | "--target" => {
+                    options.target = match value(&argument)?.as_str() {
+                        "llvm" => Target::Llvm,
+                        target => return Err(Error::Usage(format!("unknown target `{target}`"))),
+                    }
+                }
+                "-" => options.inputs.push(argument),
+                _ if argument.starts_with('-') => return Err(Error::Usage(format!("unknown option `{argument}`"))),
+                _ if command.is_none() => {
+                    command = Some(match argument.as_str() {
+                        "parse" => Command::Parse,
+                        "verify" => Command::Verify,
+                        "print" => Command::Print,
+                        "dump" => Command::Dump,
+                        "passes" => Command::Passes,
+                        "emit" => Command::Emit,
+                        "help" => Command::Help,
+                        _ => return Err(Error::Usage(format!("unknown command `{argument}`"))),
+                    })
+                }
+                _ => options.inputs.push(argument),
+            }
+        }
+
+        options.command = command.ok_or_else(|| Error::Usage("no command given".to_string()))?;
+
+        Ok(options)
+    }
+}
+
+fn verify(module: &Module, file_name: &str) -> Result<(), Error> {
+    crate::verifier::verify_module(module).map_err(|errors| Error::Verify {
+        file_name: file_name.to_string(),
+        errors,
+    })
+}
+
+/// runs a command over a single input, returning what should be output for it
+pub fn process(options: &Options, file_name: &str, source: &str) -> Result<String, Error> {
+    let mut module = ModuleParser::new().parse(source).map_err(|error| ParseError::new(source, error).with_file_name(file_name))?;
+
+    match options.command {
+        Command::Parse | Command::Help => Ok(String::new()),
+        Command::Verify => verify(&module, file_name).map(|_| String::new()),
+        Command::Print => Ok(module.to_string()),
+        Command::Dump => Ok(format!("{module:#?}\n")),
+        Command::Passes => {
+            for pass in &options.passes {
+                (pass.run)(&mut module, file_name)?;
+            }
+
+            Ok(module.to_string())
+        }
+        Command::Emit => {
+            verify(&module, file_name)?;
+
+            match options.target {
+                Target::Llvm => Ok(module.to_string()),
+            }
+        }
+    }
+}
+
+/// reads every input, runs the command over it, and writes the output
+pub fn run(options: &Options) -> Result<(), Error> {
+    if options.command == Command::Help {
+        print!("{USAGE}");
+        return Ok(());
+    }
+
+    let stdin = ["-".to_string()];
+    let inputs = if options.inputs.is_empty() { &stdin[..] } else { &options.inputs[..] };
+    let mut output = String::new();
+
+    for input in inputs {
+        let (file_name, source) = if input == "-" {
+            let mut source = String::new();
+
+            std::io::stdin().read_to_string(&mut source).map_err(|error| Error::Io {
+                path: "<stdin>".to_string(),
+                error,
+            })?;
+
+            ("<stdin>", source)
+        } else {
+            let source = std::fs::read_to_string(input).map_err(|error| Error::Io { path: input.clone(), error })?;
+
+            (input.as_str(), source)
+        };
+
+        output.push_str(&process(options, file_name, &source)?);
+    }
+
+    match &options.output {
+        Some(path) => std::fs::write(path, output).map_err(|error| Error::Io { path: path.clone(), error }),
+        None => std::io::stdout().write_all(output.as_bytes()).map_err(|error| Error::Io {
+            path: "<stdout>".to_string(),
+            error,
+        }),
+    }
+}
@@ -0,0 +1,54 @@
+use super::{process, Command, Options, Target};
+use crate::error::Error;
+
+fn options(arguments: &[&str]) -> Result<Options, Error> {
+    Options::from_arguments(arguments.iter().map(|argument| argument.to_string()))
+}
+
+/// makes sure commands, options and inputs are picked out of the arguments correctly
+#[test]
+fn argument_parsing() {
+    let parsed = options(&["print", "a.ll", "-o", "out.ll", "-", "b.ll"]).unwrap();
+    assert!(parsed.command == Command::Print);
+    assert!(parsed.inputs == ["a.ll", "-", "b.ll"]);
+    assert!(parsed.output.as_deref() == Some("out.ll"));
+
+    let parsed = options(&["--passes", "verify,verify", "passes", "--target", "llvm"]).unwrap();
+    assert!(parsed.command == Command::Passes);
+    assert!(parsed.passes.len() == 2);
+    assert!(parsed.target == Target::Llvm);
+    assert!(parsed.inputs.is_empty());
+
+    assert!(options(&["parse", "--help"]).unwrap().command == Command::Help);
+
+    // usage errors have their own exit code
+    for arguments in [&[][..], &["frobnicate"], &["print", "-o"], &["print", "--bogus"], &["passes", "-p", "inline"], &["emit", "-t", "z80"]] {
+        let error = options(arguments).unwrap_err();
+        assert!(matches!(error, Error::Usage(_)));
+        assert!(error.exit_code() == 2);
+    }
+}
+
+/// makes sure each command does what it says with its input
+#[test]
+fn commands() {
+    let valid = "define i32 @f(i32 %x) {\n    %y = add i32 %x, 1\n    ret i32 %y\n}\n";
+    let invalid = "define i32 @f(i32 %x) {\n    %y = add i32 %x, %z\n    ret i32 %y\n}\n";
+    let run = |arguments: &[&str], source: &str| process(&options(arguments).unwrap(), "test.ll", source);
+
+    assert!(run(&["parse"], valid).unwrap().is_empty());
+    assert!(run(&["verify"], valid).unwrap().is_empty());
+    assert!(run(&["print"], valid).unwrap() == valid);
+    assert!(run(&["dump"], valid).unwrap().starts_with("Module {"));
+    assert!(run(&["passes", "-p", "verify"], valid).unwrap() == valid);
+    assert!(run(&["emit"], valid).unwrap() == valid);
+
+    // only the commands that verify their input should reject this
+    assert!(run(&["print"], invalid).is_ok());
+    let error = run(&["emit"], invalid).unwrap_err();
+    assert!(error.exit_code() == 1);
+    assert!(error.to_string() == "error: use of undefined value %z\n --> test.ll: @f, block %0, instruction 0\n");
+
+    let error = run(&["parse"], "define").unwrap_err();
+    assert!(matches!(&error, Error::Parse(error) if error.file_name.as_deref() == Some("test.ll")));
+}
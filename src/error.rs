@@ -0,0 +1,221 @@
+use lalrpop_util::lexer::Token;
+use std::fmt;
+
+/// a position in a source file. both the line and column start at 1, and the column is counted in characters rather than bytes
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl SourceLocation {
+    /// finds the line and column of a byte offset into the source
+    pub fn from_offset(source: &str, offset: usize) -> Self {
+        let before = &source[..offset.min(source.len())];
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// an error found by an action in the grammar rather than by the parser itself, like a constant that doesn't fit its type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrammarError {
+    pub message: &'static str,
+    /// the byte offsets of the start and end of the source the error is about
+    pub span: (usize, usize),
+}
+
+/// an error encountered while parsing LLVM IR. when displayed, this renders the offending line of source with the error underlined
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    /// descriptions of the tokens that would've been accepted instead, in LLVM syntax
+    pub expected: Vec<String>,
+    /// where the error starts
+    pub location: SourceLocation,
+    /// how many characters of the line the error spans
+    pub length: usize,
+    /// the line of source the error is on, without its line ending
+    pub source_line: String,
+    /// the name of the file that was being parsed, if any
+    pub file_name: Option<String>,
+}
+
+impl ParseError {
+    /// converts an error from the parser, using the source it was parsing to find line and column numbers
+    pub fn new(source: &str, error: lalrpop_util::ParseError<usize, Token<'_>, GrammarError>) -> Self {
+        let (message, expected, (start, end)) = match error {
+            lalrpop_util::ParseError::InvalidToken { location } => {
+                // the lexer doesn't know where an invalid token ends, so the rest of the word is used
+                let word = source.get(location..).unwrap_or_default();
+                let word = &word[..word.find(|c: char| c.is_whitespace() || ",()[]{}<>=".contains(c)).unwrap_or(word.len())];
+
+                (format!("invalid token `{word}`"), Vec::new(), (location, location + word.len()))
+            }
+            lalrpop_util::ParseError::UnrecognizedEof { location, expected } => ("unexpected end of file".to_string(), expected, (location, location)),
+            lalrpop_util::ParseError::UnrecognizedToken { token: (start, token, end), expected } => (format!("unexpected {}", describe_token(token.1)), expected, (start, end)),
+            lalrpop_util::ParseError::ExtraToken { token: (start, token, end) } => (format!("extra {} after the end of input", describe_token(token.1)), Vec::new(), (start, end)),
+            lalrpop_util::ParseError::User { error } => (error.message.to_string(), Vec::new(), error.span),
+        };
+
+        let mut described = Vec::<String>::new();
+
+        for token in expected {
+            let description = describe_expected(&token);
+
+            if !described.contains(&description) {
+                described.push(description);
+            }
+        }
+
+        let start = start.min(source.len());
+        let line_start = source[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let line_end = source[start..].find('\n').map(|i| start + i).unwrap_or(source.len());
+        // tokens that span multiple lines (or are newlines themselves) are only underlined up to the end of the line
+        let end = end.clamp(start, line_end);
+
+        Self {
+            message,
+            expected: described,
+            location: SourceLocation::from_offset(source, start),
+            length: source[start..end].chars().count().max(1),
+            source_line: source[line_start..line_end].to_string(),
+            file_name: None,
+        }
+    }
+
+    /// sets the name of the file shown in the error
+    pub fn with_file_name(mut self, name: impl Into<String>) -> Self {
+        self.file_name = Some(name.into());
+        self
+    }
+}
+
+/// describes a token that was found in the source
+fn describe_token(token: &str) -> String {
+    if token.trim().is_empty() {
+        "newline".to_string()
+    } else {
+        format!("`{}`", token.trim_end())
+    }
+}
+
+/// turns the name of a terminal in the grammar into something readable. literal tokens are shown as-is, and regular expressions are described by what they match
+fn describe_expected(terminal: &str) -> String {
+    let Some(pattern) = terminal.strip_prefix("r#\"").and_then(|t| t.strip_suffix("\"#")) else {
+        return format!("`{}`", terminal.trim_matches('"'));
+    };
+
+    let description = match pattern {
+        "\\\\n" => "newline",
+        "[0-9]+" | "-[0-9]+" => "integer",
+        "i[0-9]+" => "integer type",
+        _ if pattern.starts_with('%') => "local identifier",
+        _ if pattern.starts_with('@') => "global identifier",
+        _ if pattern.starts_with('#') => "attribute group",
+        _ if pattern.starts_with("![0-9]") => "metadata node",
+        _ if pattern.starts_with("!\\\\") => "metadata string",
+        _ if pattern.starts_with("![A-Z]") => "specialized metadata node",
+        _ if pattern.starts_with("![-") => "metadata name",
+        _ if pattern.starts_with(',') => "metadata attachment",
+        _ if pattern.starts_with("[A-Z]") => "named constant",
+        _ if pattern.starts_with("[a-zA-Z]") => "field name",
+        _ if pattern.starts_with("c\\\\") => "character array",
+        _ if pattern.starts_with("\\\\\\\"") => "string",
+        _ if pattern.starts_with("\\\\$") => "comdat",
+        _ if pattern.starts_with("u0x") || pattern.starts_with("s0x") => "hexadecimal integer",
+        _ if pattern.starts_with("[-+]?") || pattern.starts_with("0x") => "floating point constant",
+        _ if pattern.starts_with("[^") => "block label",
+        _ => return format!("/{pattern}/"),
+    };
+
+    description.to_string()
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "error: {}", self.message)?;
+
+        let file_name = self.file_name.as_deref().unwrap_or("<input>");
+        let location = self.location;
+        let gutter = " ".repeat(location.line.to_string().len());
+        // the caret has to line up with the source, so tabs before it are kept as tabs
+        let padding = self.source_line.chars().take(location.column - 1).map(|c| if c == '\t' { '\t' } else { ' ' }).collect::<String>();
+
+        writeln!(f, "{gutter}--> {file_name}:{location}")?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{} | {}", location.line, self.source_line)?;
+        writeln!(f, "{gutter} | {padding}{}", "^".repeat(self.length))?;
+
+        if !self.expected.is_empty() {
+            let expected = self.expected.join(", ");
+
+            if self.expected.len() == 1 {
+                writeln!(f, "{gutter} = expected {expected}")?;
+            } else {
+                writeln!(f, "{gutter} = expected one of {expected}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// anything that can go wrong while running the compiler
+#[derive(Debug)]
+pub enum Error {
+    /// the command line arguments didn't make sense
+    Usage(String),
+    /// a file couldn't be read or written
+    Io { path: String, error: std::io::Error },
+    Parse(Box<ParseError>),
+    /// the input parsed, but didn't pass verification
+    Verify { file_name: String, errors: Vec<crate::verifier::VerifierError> },
+}
+
+impl Error {
+    /// the exit code the compiler should exit with for this error. usage errors are distinguished from errors in the input
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::Usage(_) => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Usage(message) => writeln!(f, "error: {message}\n\n{}", crate::driver::USAGE),
+            Self::Io { path, error } => writeln!(f, "error: {path}: {error}"),
+            Self::Parse(error) => error.fmt(f),
+            Self::Verify { file_name, errors } => {
+                for error in errors {
+                    writeln!(f, "error: {}\n --> {file_name}: {}", error.message, error.location)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Self {
+        Self::Parse(Box::new(error))
+    }
+}
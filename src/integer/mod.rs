@@ -0,0 +1,336 @@
+use std::cmp::Ordering;
+
+#[cfg(test)]
+pub mod test;
+
+/// an integer with any bit width, stored in two's complement. like in LLVM, integers aren't signed or unsigned themselves, that's up to the operations done on them.
+/// operations on two integers expect them to have the same bit width, and panic if they don't
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Integer {
+    bit_width: usize,
+    /// the bits of the integer 64 at a time, starting from the least significant. bits past the bit width are always clear
+    words: Vec<u64>,
+}
+
+impl Integer {
+    /// zero with the given bit width, which can't be zero
+    pub fn zero(bit_width: usize) -> Self {
+        assert!(bit_width > 0, "integers must have at least one bit");
+
+        Self {
+            bit_width,
+            words: vec![0; bit_width.div_ceil(64)],
+        }
+    }
+
+    /// an integer made from the low bits of `value`
+    pub fn from_u64(bit_width: usize, value: u64) -> Self {
+        let mut words = Self::zero(bit_width).words;
+        words[0] = value;
+        Self::from_words(bit_width, words)
+    }
+
+    /// an integer made from the low bits of `value`, which is sign extended if the bit width is wider than 64 bits
+    pub fn from_i64(bit_width: usize, value: i64) -> Self {
+        Self::from_u64(64, value as u64).sign_extend(bit_width)
+    }
+
+    /// the integer with every bit set, which is -1 as a signed number or the largest unsigned number
+    pub fn all_ones(bit_width: usize) -> Self {
+        Self::zero(bit_width).not()
+    }
+
+    /// the smallest signed number with the given bit width, which only has the most significant bit set
+    pub fn signed_minimum(bit_width: usize) -> Self {
+        Self::from_u64(bit_width, 1).shift_left(bit_width - 1)
+    }
+
+    /// parses a number written in the given radix without a sign, giving it the smallest bit width that can hold it
+    pub fn parse_unsigned(digits: &str, radix: u32) -> Option<Self> {
+        if digits.is_empty() {
+            return None;
+        }
+
+        let mut words = vec![0];
+
+        for digit in digits.chars() {
+            let mut carry = u128::from(digit.to_digit(radix)?);
+
+            for word in &mut words {
+                let result = u128::from(*word) * u128::from(radix) + carry;
+                *word = result as u64;
+                carry = result >> 64;
+            }
+
+            if carry != 0 {
+                words.push(carry as u64);
+            }
+        }
+
+        let integer = Self::from_words(words.len() * 64, words);
+        Some(integer.zero_extend(integer.active_bits().max(1)))
+    }
+
+    /// makes an integer out of words that might have bits set past the bit width
+    fn from_words(bit_width: usize, mut words: Vec<u64>) -> Self {
+        if !bit_width.is_multiple_of(64) {
+            *words.last_mut().unwrap() &= u64::MAX >> (64 - bit_width % 64);
+        }
+
+        Self { bit_width, words }
+    }
+
+    fn check_bit_width(&self, other: &Self) {
+        assert!(self.bit_width == other.bit_width, "bit widths {} and {} don't match", self.bit_width, other.bit_width);
+    }
+
+    pub fn bit_width(&self) -> usize {
+        self.bit_width
+    }
+
+    /// the bits of this integer 64 at a time, starting from the least significant
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    pub fn bit(&self, index: usize) -> bool {
+        index < self.bit_width && (self.words[index / 64] >> (index % 64)) & 1 == 1
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// whether the most significant bit is set, which makes this negative as a signed number
+    pub fn is_negative(&self) -> bool {
+        self.bit(self.bit_width - 1)
+    }
+
+    /// the number of bits needed to hold this as an unsigned number
+    pub fn active_bits(&self) -> usize {
+        self.words.iter().rposition(|word| *word != 0).map_or(0, |index| index * 64 + 64 - self.words[index].leading_zeros() as usize)
+    }
+
+    /// this integer as an unsigned number, if it fits in a `u64`
+    pub fn to_u64(&self) -> Option<u64> {
+        self.fits_unsigned(64).then_some(self.words[0])
+    }
+
+    /// this integer as a signed number, if it fits in an `i64`
+    pub fn to_i64(&self) -> Option<i64> {
+        self.fits_signed(64).then(|| self.sign_extend(64).words[0] as i64)
+    }
+
+    /// whether this integer can be truncated to `bit_width` bits without changing its value as an unsigned number
+    pub fn fits_unsigned(&self, bit_width: usize) -> bool {
+        self.active_bits() <= bit_width
+    }
+
+    /// whether this integer can be truncated to `bit_width` bits without changing its value as a signed number
+    pub fn fits_signed(&self, bit_width: usize) -> bool {
+        &self.sign_extend(bit_width).sign_extend(self.bit_width) == self
+    }
+
+    /// changes the bit width of this integer, filling any new bits with copies of the sign bit if `is_signed` is set or zeros otherwise
+    fn resize(&self, bit_width: usize, is_signed: bool) -> Self {
+        let fill = if is_signed && self.is_negative() { u64::MAX } else { 0 };
+        let mut words = self.words.clone();
+
+        // the unused bits of the last word are filled in too, since they become part of the integer if it gets wider
+        if !self.bit_width.is_multiple_of(64) {
+            *words.last_mut().unwrap() |= fill << (self.bit_width % 64);
+        }
+
+        words.resize(bit_width.div_ceil(64), fill);
+        Self::from_words(bit_width, words)
+    }
+
+    /// zero extends this integer to the given bit width, or truncates it if the bit width is narrower
+    pub fn zero_extend(&self, bit_width: usize) -> Self {
+        self.resize(bit_width, false)
+    }
+
+    /// sign extends this integer to the given bit width, or truncates it if the bit width is narrower
+    pub fn sign_extend(&self, bit_width: usize) -> Self {
+        self.resize(bit_width, true)
+    }
+
+    fn zip_words(&self, other: &Self, operation: impl FnMut((&u64, &u64)) -> u64) -> Self {
+        self.check_bit_width(other);
+        Self::from_words(self.bit_width, self.words.iter().zip(&other.words).map(operation).collect())
+    }
+
+    pub fn and(&self, other: &Self) -> Self {
+        self.zip_words(other, |(a, b)| a & b)
+    }
+
+    pub fn or(&self, other: &Self) -> Self {
+        self.zip_words(other, |(a, b)| a | b)
+    }
+
+    pub fn xor(&self, other: &Self) -> Self {
+        self.zip_words(other, |(a, b)| a ^ b)
+    }
+
+    /// flips every bit
+    pub fn not(&self) -> Self {
+        Self::from_words(self.bit_width, self.words.iter().map(|word| !word).collect())
+    }
+
+    pub fn wrapping_add(&self, other: &Self) -> Self {
+        let mut carry = false;
+
+        self.zip_words(other, |(a, b)| {
+            let (sum, first_carry) = a.overflowing_add(*b);
+            let (sum, second_carry) = sum.overflowing_add(carry.into());
+            carry = first_carry || second_carry;
+            sum
+        })
+    }
+
+    pub fn wrapping_sub(&self, other: &Self) -> Self {
+        self.wrapping_add(&other.wrapping_neg())
+    }
+
+    pub fn wrapping_neg(&self) -> Self {
+        self.not().wrapping_add(&Self::from_u64(self.bit_width, 1))
+    }
+
+    pub fn wrapping_mul(&self, other: &Self) -> Self {
+        self.check_bit_width(other);
+
+        let length = self.words.len();
+        let mut words = vec![0; length];
+
+        for (i, a) in self.words.iter().enumerate().filter(|(_, a)| **a != 0) {
+            let mut carry = 0;
+
+            for (j, b) in other.words[..length - i].iter().enumerate() {
+                let result = u128::from(words[i + j]) + u128::from(*a) * u128::from(*b) + carry;
+                words[i + j] = result as u64;
+                carry = result >> 64;
+            }
+        }
+
+        Self::from_words(self.bit_width, words)
+    }
+
+    /// shifts towards the most significant bit, which gives zero if `amount` is at least the bit width
+    pub fn shift_left(&self, amount: usize) -> Self {
+        if amount >= self.bit_width {
+            return Self::zero(self.bit_width);
+        }
+
+        let (word_shift, bit_shift) = (amount / 64, amount % 64);
+        let words = (0..self.words.len())
+            .map(|index| match index.checked_sub(word_shift) {
+                Some(source) if bit_shift != 0 && source > 0 => self.words[source] << bit_shift | self.words[source - 1] >> (64 - bit_shift),
+                Some(source) => self.words[source] << bit_shift,
+                None => 0,
+            })
+            .collect();
+
+        Self::from_words(self.bit_width, words)
+    }
+
+    /// shifts towards the least significant bit, filling the most significant bits with zeros
+    pub fn logical_shift_right(&self, amount: usize) -> Self {
+        if amount >= self.bit_width {
+            return Self::zero(self.bit_width);
+        }
+
+        let (word_shift, bit_shift) = (amount / 64, amount % 64);
+        let words = (0..self.words.len())
+            .map(|index| {
+                let source = index + word_shift;
+
+                match (self.words.get(source), self.words.get(source + 1)) {
+                    (Some(low), Some(high)) if bit_shift != 0 => low >> bit_shift | high << (64 - bit_shift),
+                    (Some(low), _) => low >> bit_shift,
+                    (None, _) => 0,
+                }
+            })
+            .collect();
+
+        Self::from_words(self.bit_width, words)
+    }
+
+    /// shifts towards the least significant bit, filling the most significant bits with copies of the sign bit
+    pub fn arithmetic_shift_right(&self, amount: usize) -> Self {
+        if self.is_negative() {
+            self.not().logical_shift_right(amount).not()
+        } else {
+            self.logical_shift_right(amount)
+        }
+    }
+
+    /// divides two unsigned numbers, giving the quotient and the remainder. this returns `None` when dividing by zero
+    pub fn unsigned_divide(&self, other: &Self) -> Option<(Self, Self)> {
+        self.check_bit_width(other);
+
+        if other.is_zero() {
+            return None;
+        }
+
+        // divisors that fit in a word can be divided out a word at a time
+        if let Some(divisor) = other.to_u64() {
+            let divisor = u128::from(divisor);
+            let mut remainder = 0;
+            let mut words = self
+                .words
+                .iter()
+                .rev()
+                .map(|word| {
+                    let dividend = remainder << 64 | u128::from(*word);
+                    remainder = dividend % divisor;
+                    (dividend / divisor) as u64
+                })
+                .collect::<Vec<_>>();
+            words.reverse();
+
+            return Some((Self::from_words(self.bit_width, words), Self::from_u64(self.bit_width, remainder as u64)));
+        }
+
+        // otherwise it's long division a bit at a time, with an extra bit so the remainder can't overflow when it's shifted
+        let bit_width = self.bit_width + 1;
+        let divisor = other.zero_extend(bit_width);
+        let mut quotient = Self::zero(bit_width);
+        let mut remainder = Self::zero(bit_width);
+
+        for index in (0..self.active_bits()).rev() {
+            remainder = remainder.shift_left(1);
+            remainder.words[0] |= u64::from(self.bit(index));
+
+            if remainder.unsigned_cmp(&divisor) != Ordering::Less {
+                remainder = remainder.wrapping_sub(&divisor);
+                quotient.words[index / 64] |= 1 << (index % 64);
+            }
+        }
+
+        Some((quotient.zero_extend(self.bit_width), remainder.zero_extend(self.bit_width)))
+    }
+
+    /// divides two signed numbers, rounding the quotient towards zero and giving the remainder the sign of `self`.
+    /// dividing the smallest signed number by -1 wraps around, and this returns `None` when dividing by zero
+    pub fn signed_divide(&self, other: &Self) -> Option<(Self, Self)> {
+        let magnitude = |integer: &Self| if integer.is_negative() { integer.wrapping_neg() } else { integer.clone() };
+        let (quotient, remainder) = magnitude(self).unsigned_divide(&magnitude(other))?;
+        let quotient = if self.is_negative() != other.is_negative() { quotient.wrapping_neg() } else { quotient };
+        let remainder = if self.is_negative() { remainder.wrapping_neg() } else { remainder };
+
+        Some((quotient, remainder))
+    }
+
+    pub fn unsigned_cmp(&self, other: &Self) -> Ordering {
+        self.check_bit_width(other);
+        self.words.iter().rev().cmp(other.words.iter().rev())
+    }
+
+    pub fn signed_cmp(&self, other: &Self) -> Ordering {
+        match (self.is_negative(), other.is_negative()) {
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            _ => self.unsigned_cmp(other),
+        }
+    }
+}
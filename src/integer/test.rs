@@ -0,0 +1,153 @@
+use super::Integer;
+
+/// widths on either side of a word boundary, along with the narrowest width there is
+const BIT_WIDTHS: [usize; 6] = [1, 8, 63, 64, 65, 128];
+
+fn mask(bit_width: usize) -> u128 {
+    u128::MAX >> (128 - bit_width)
+}
+
+/// sign extends the low `bit_width` bits of a number to 128 bits
+fn signed(value: u128, bit_width: usize) -> i128 {
+    ((value << (128 - bit_width)) as i128) >> (128 - bit_width)
+}
+
+fn from_u128(bit_width: usize, value: u128) -> Integer {
+    let high = Integer::from_u64(128, (value >> 64) as u64).shift_left(64);
+    high.or(&Integer::from_u64(128, value as u64)).zero_extend(bit_width)
+}
+
+fn to_u128(integer: &Integer) -> u128 {
+    integer.words().iter().rev().fold(0, |result, word| result << 64 | u128::from(*word))
+}
+
+/// a few values with interesting bit patterns for every width, truncated to fit it
+fn samples(bit_width: usize) -> Vec<u128> {
+    let mut samples = vec![0, 1, 2, 3, 7, 1 << 63, u128::from(u64::MAX), 1 << 64, 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210, 1 << (bit_width - 1), (1 << (bit_width - 1)) - 1, u128::MAX];
+    samples.iter_mut().for_each(|sample| *sample &= mask(bit_width));
+    samples.sort();
+    samples.dedup();
+    samples
+}
+
+/// makes sure arithmetic wraps around at the bit width, comparing against 128 bit arithmetic
+#[test]
+fn arithmetic() {
+    for bit_width in BIT_WIDTHS {
+        for a in samples(bit_width) {
+            for b in samples(bit_width) {
+                let (x, y) = (from_u128(bit_width, a), from_u128(bit_width, b));
+
+                assert!(to_u128(&x.wrapping_add(&y)) == a.wrapping_add(b) & mask(bit_width));
+                assert!(to_u128(&x.wrapping_sub(&y)) == a.wrapping_sub(b) & mask(bit_width));
+                assert!(to_u128(&x.wrapping_mul(&y)) == a.wrapping_mul(b) & mask(bit_width));
+                assert!(x.unsigned_cmp(&y) == a.cmp(&b));
+                assert!(x.signed_cmp(&y) == signed(a, bit_width).cmp(&signed(b, bit_width)));
+            }
+        }
+    }
+}
+
+/// makes sure division rounds towards zero, gives remainders the sign of the dividend, and refuses to divide by zero
+#[test]
+fn division() {
+    for bit_width in BIT_WIDTHS {
+        for a in samples(bit_width) {
+            for b in samples(bit_width) {
+                let (x, y) = (from_u128(bit_width, a), from_u128(bit_width, b));
+                let unsigned = x.unsigned_divide(&y).map(|(quotient, remainder)| (to_u128(&quotient), to_u128(&remainder)));
+                let signed_result = x.signed_divide(&y).map(|(quotient, remainder)| (to_u128(&quotient), to_u128(&remainder)));
+
+                if b == 0 {
+                    assert!(unsigned.is_none() && signed_result.is_none());
+                    continue;
+                }
+
+                let (sa, sb) = (signed(a, bit_width), signed(b, bit_width));
+                assert!(unsigned == Some((a / b, a % b)));
+                // the smallest signed number divided by -1 wraps around to itself
+                assert!(signed_result == Some((sa.wrapping_div(sb) as u128 & mask(bit_width), sa.wrapping_rem(sb) as u128 & mask(bit_width))));
+            }
+        }
+    }
+
+    let minimum = Integer::signed_minimum(64);
+    assert!(minimum.signed_divide(&Integer::all_ones(64)) == Some((minimum.clone(), Integer::zero(64))));
+    assert!(Integer::from_i64(65, -7).signed_divide(&Integer::from_i64(65, 2)) == Some((Integer::from_i64(65, -3), Integer::from_i64(65, -1))));
+}
+
+/// makes sure shifts fill in the right bits, including shifts by whole words and by at least the bit width
+#[test]
+fn shifts() {
+    for bit_width in BIT_WIDTHS {
+        for a in samples(bit_width) {
+            let x = from_u128(bit_width, a);
+
+            for amount in [0, 1, 7, 63, 64, 65, 127, bit_width - 1, bit_width, bit_width + 1, 200] {
+                let (left, logical, arithmetic) = if amount >= bit_width {
+                    (0, 0, if signed(a, bit_width) < 0 { mask(bit_width) } else { 0 })
+                } else {
+                    (a << amount & mask(bit_width), a >> amount, (signed(a, bit_width) >> amount) as u128 & mask(bit_width))
+                };
+
+                assert!(to_u128(&x.shift_left(amount)) == left);
+                assert!(to_u128(&x.logical_shift_right(amount)) == logical);
+                assert!(to_u128(&x.arithmetic_shift_right(amount)) == arithmetic);
+            }
+        }
+    }
+}
+
+/// makes sure extending and truncating keep or drop the right bits, and that values are only said to fit when they do
+#[test]
+fn resizing() {
+    for bit_width in BIT_WIDTHS {
+        for a in samples(bit_width) {
+            let x = from_u128(bit_width, a);
+
+            for new_width in BIT_WIDTHS {
+                let zero_extended = x.zero_extend(new_width);
+                let sign_extended = x.sign_extend(new_width);
+
+                assert!(zero_extended.bit_width() == new_width && sign_extended.bit_width() == new_width);
+                assert!(to_u128(&zero_extended) == a & mask(new_width));
+                assert!(to_u128(&sign_extended) == signed(a, bit_width) as u128 & mask(new_width));
+                assert!(x.fits_unsigned(new_width) == (a & mask(new_width) == a));
+                assert!(x.fits_signed(new_width) == (signed(signed(a, bit_width) as u128 & mask(new_width), new_width) == signed(a, bit_width)));
+            }
+
+            assert!(x.to_u64() == u64::try_from(a).ok());
+            assert!(x.to_i64() == i64::try_from(signed(a, bit_width)).ok());
+            assert!(x.is_negative() == (signed(a, bit_width) < 0));
+        }
+    }
+
+    assert!(Integer::all_ones(1).is_negative());
+    assert!(Integer::from_i64(128, -2).to_i64() == Some(-2));
+}
+
+/// makes sure unsigned numbers are given the narrowest bit width that holds them, and that anything but digits in the radix is rejected
+#[test]
+fn parse_unsigned() {
+    let parsed = |digits: &str, radix| Integer::parse_unsigned(digits, radix).map(|integer| (integer.bit_width(), to_u128(&integer)));
+
+    assert!(parsed("0", 10) == Some((1, 0)));
+    assert!(parsed("0000", 10) == Some((1, 0)));
+    assert!(parsed("1", 10) == Some((1, 1)));
+    assert!(parsed("0001", 2) == Some((1, 1)));
+    assert!(parsed("255", 10) == Some((8, 255)));
+    assert!(parsed("100", 16) == Some((9, 256)));
+    assert!(parsed("18446744073709551615", 10) == Some((64, u128::from(u64::MAX))));
+    assert!(parsed("18446744073709551616", 10) == Some((65, 1 << 64)));
+    assert!(parsed("ffffffffffffffffffffffffffffffff", 16) == Some((128, u128::MAX)));
+    assert!(parsed("340282366920938463463374607431768211455", 10) == Some((128, u128::MAX)));
+
+    let huge = Integer::parse_unsigned("340282366920938463463374607431768211456", 10).unwrap();
+    assert!(huge.bit_width() == 129 && huge.words() == [0, 0, 1]);
+
+    assert!(parsed("", 10).is_none());
+    assert!(parsed("-1", 10).is_none());
+    assert!(parsed("12a", 10).is_none());
+    assert!(parsed("g", 16).is_none());
+    assert!(parsed("2", 2).is_none());
+}
@@ -1,7 +1,10 @@
-use crate::types::{AddressSpace, ParameterAttribute, Type};
+use crate::{
+    integer::Integer,
+    types::{AddressSpace, FloatingPointKind, FunctionAttribute, ParameterAttribute, Type, TypeDefinitions},
+};
 use std::sync::Arc;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct AllowedWrapping {
     pub can_wrap_unsigned: bool,
     pub can_wrap_signed: bool,
@@ -16,29 +19,94 @@ impl Default for AllowedWrapping {
     }
 }
 
-#[derive(Debug, Clone)]
+/// assumptions that floating point operations are allowed to make to enable optimizations, see https://llvm.org/docs/LangRef.html#fast-math-flags
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct FastMathFlags {
+    /// nnan
+    pub no_nans: bool,
+    /// ninf
+    pub no_infinities: bool,
+    /// nsz
+    pub no_signed_zeros: bool,
+    /// arcp
+    pub allow_reciprocal: bool,
+    /// contract
+    pub allow_contraction: bool,
+    /// afn
+    pub approximate_functions: bool,
+    /// reassoc
+    pub allow_reassociation: bool,
+}
+
+impl FastMathFlags {
+    /// every flag set at once, which is written as `fast`
+    pub const FAST: Self = Self {
+        no_nans: true,
+        no_infinities: true,
+        no_signed_zeros: true,
+        allow_reciprocal: true,
+        allow_contraction: true,
+        approximate_functions: true,
+        allow_reassociation: true,
+    };
+}
+
+impl std::ops::BitOr for FastMathFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self {
+            no_nans: self.no_nans || rhs.no_nans,
+            no_infinities: self.no_infinities || rhs.no_infinities,
+            no_signed_zeros: self.no_signed_zeros || rhs.no_signed_zeros,
+            allow_reciprocal: self.allow_reciprocal || rhs.allow_reciprocal,
+            allow_contraction: self.allow_contraction || rhs.allow_contraction,
+            approximate_functions: self.approximate_functions || rhs.approximate_functions,
+            allow_reassociation: self.allow_reassociation || rhs.allow_reassociation,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Instruction {
+    /// fneg
+    FloatNegate { fast_math_flags: FastMathFlags, value: Arc<Value> },
     /// add
     Add {
         left_hand_side: Arc<Value>,
         right_hand_side: Arc<Value>,
         allowed_wrapping: AllowedWrapping,
     },
-    // TODO: fadd
+    /// fadd
+    FloatAdd {
+        left_hand_side: Arc<Value>,
+        right_hand_side: Arc<Value>,
+        fast_math_flags: FastMathFlags,
+    },
     /// sub
     Subtract {
         left_hand_side: Arc<Value>,
         right_hand_side: Arc<Value>,
         allowed_wrapping: AllowedWrapping,
     },
-    // TODO: fsub
+    /// fsub
+    FloatSubtract {
+        left_hand_side: Arc<Value>,
+        right_hand_side: Arc<Value>,
+        fast_math_flags: FastMathFlags,
+    },
     /// mul
     Multiply {
         left_hand_side: Arc<Value>,
         right_hand_side: Arc<Value>,
         allowed_wrapping: AllowedWrapping,
     },
-    // TODO: fmul
+    /// fmul
+    FloatMultiply {
+        left_hand_side: Arc<Value>,
+        right_hand_side: Arc<Value>,
+        fast_math_flags: FastMathFlags,
+    },
     /// udiv
     UnsignedDivide {
         left_hand_side: Arc<Value>,
@@ -51,12 +119,22 @@ pub enum Instruction {
         right_hand_side: Arc<Value>,
         is_exact: bool,
     },
-    // TODO: fdiv
+    /// fdiv
+    FloatDivide {
+        left_hand_side: Arc<Value>,
+        right_hand_side: Arc<Value>,
+        fast_math_flags: FastMathFlags,
+    },
     /// urem
     UnsignedRemainder { left_hand_side: Arc<Value>, right_hand_side: Arc<Value> },
     /// srem
     SignedRemainder { left_hand_side: Arc<Value>, right_hand_side: Arc<Value> },
-    // TODO: frem
+    /// frem
+    FloatRemainder {
+        left_hand_side: Arc<Value>,
+        right_hand_side: Arc<Value>,
+        fast_math_flags: FastMathFlags,
+    },
     /// shl
     ShiftLeft {
         left_hand_side: Arc<Value>,
@@ -85,7 +163,13 @@ pub enum Instruction {
     },
     /// xor
     ExclusiveOr { left_hand_side: Arc<Value>, right_hand_side: Arc<Value> },
-    // TODO: extractelement, insertelement, shufflevector
+    /// extractelement
+    ExtractElement { vector: Arc<Value>, index: Arc<Value> },
+    /// insertelement
+    InsertElement { vector: Arc<Value>, value: Arc<Value>, index: Arc<Value> },
+    /// shufflevector. the mask is a constant vector of `i32`s, where each element picks an element out of both vectors put together (or is `poison` or `undef`).
+    /// the result has as many elements as the mask, and masks for scalable vectors can only be `zeroinitializer`, `undef` or `poison`
+    ShuffleVector { first: Arc<Value>, second: Arc<Value>, mask: Arc<Value> },
     /// extractvalue
     ExtractValue { aggregate: Arc<Value>, indices: Vec<usize> },
     /// insertvalue
@@ -132,7 +216,28 @@ pub enum Instruction {
     },
     /// fence
     Fence { ordering: Ordering, sync_scope: Option<String> },
-    // todo: cmpxchg, atomicrmw
+    /// cmpxchg
+    CompareExchange {
+        is_weak: bool,
+        is_volatile: bool,
+        pointer: Arc<Value>,
+        compare_value: Arc<Value>,
+        new_value: Arc<Value>,
+        sync_scope: Option<String>,
+        success_ordering: Ordering,
+        failure_ordering: Ordering,
+        alignment: Option<usize>,
+    },
+    /// atomicrmw
+    AtomicReadModifyWrite {
+        is_volatile: bool,
+        operation: AtomicOperation,
+        pointer: Arc<Value>,
+        value: Arc<Value>,
+        sync_scope: Option<String>,
+        ordering: Ordering,
+        alignment: Option<usize>,
+    },
     /// getelementptr
     GetElementPointer {
         kind: GetPointerKind,
@@ -146,7 +251,26 @@ pub enum Instruction {
     ZeroExtend { value: Arc<Value>, new_type: Type },
     /// sext (🤨)
     SignExtend { value: Arc<Value>, new_type: Type },
-    // TODO: fptrunc, fpext, fptoui, uitofp, sitofp
+    /// fptrunc
+    FloatTruncate {
+        fast_math_flags: FastMathFlags,
+        value: Arc<Value>,
+        new_type: Type,
+    },
+    /// fpext
+    FloatExtend {
+        fast_math_flags: FastMathFlags,
+        value: Arc<Value>,
+        new_type: Type,
+    },
+    /// fptoui
+    FloatToUnsignedInteger { value: Arc<Value>, new_type: Type },
+    /// fptosi
+    FloatToSignedInteger { value: Arc<Value>, new_type: Type },
+    /// uitofp
+    UnsignedIntegerToFloat { value: Arc<Value>, new_type: Type },
+    /// sitofp
+    SignedIntegerToFloat { value: Arc<Value>, new_type: Type },
     /// ptrtoint
     PointerToInteger { value: Arc<Value>, new_type: Type },
     /// inttoptr
@@ -161,21 +285,45 @@ pub enum Instruction {
         left_hand_side: Arc<Value>,
         right_hand_side: Arc<Value>,
     },
-    // TODO: fcmp, phi
+    /// fcmp
+    CompareFloats {
+        fast_math_flags: FastMathFlags,
+        comparison: FloatComparison,
+        left_hand_side: Arc<Value>,
+        right_hand_side: Arc<Value>,
+    },
+    /// phi
+    Phi {
+        fast_math_flags: FastMathFlags,
+        value_type: Type,
+        incoming: Vec<IncomingValue>,
+    },
     /// select
-    Select { condition: Arc<Value>, true_value: Arc<Value>, false_value: Arc<Value> },
+    Select {
+        fast_math_flags: FastMathFlags,
+        condition: Arc<Value>,
+        true_value: Arc<Value>,
+        false_value: Arc<Value>,
+    },
     /// freeze
     Freeze { value: Arc<Value> },
     /// call
     Call {
         tail_call_hint: TailCallHint,
+        fast_math_flags: FastMathFlags,
         calling_convention: Option<String>,
         return_value_attributes: Vec<ParameterAttribute>,
         address_space: Option<AddressSpace>,
+        /// the type of the function being called. calls written in the short form, which only gives the return type, get a function type made from the types of their arguments
         function_type: Type,
-        function_name: String,
-        function_arguments: Vec<Arc<Value>>,
-        // TODO: function attributes, operand bundles
+        /// the function being called, which is usually a global but can be any pointer
+        callee: Arc<Value>,
+        function_arguments: Vec<CallArgument>,
+        /// function attributes that only apply to this call
+        function_attributes: Vec<FunctionAttribute>,
+        /// the IDs of the attribute groups (`#0`) this call references
+        attribute_groups: Vec<usize>,
+        operand_bundles: Vec<OperandBundle>,
     },
     /// call asm
     CallAssembly {
@@ -184,9 +332,381 @@ pub enum Instruction {
         hints: AssemblyCallHints,
         template: String,
         operand_constraints: String,
-        arguments: Vec<Arc<Value>>,
+        arguments: Vec<CallArgument>,
+        function_attributes: Vec<FunctionAttribute>,
+        attribute_groups: Vec<usize>,
+    },
+    /// landingpad
+    LandingPad {
+        result_type: Type,
+        /// whether this landing pad is entered to clean up even when none of its clauses match
+        is_cleanup: bool,
+        clauses: Vec<LandingPadClause>,
     },
-    // TODO: va_arg, landingpad, catchpad, cleanuppad
+    /// catchpad
+    CatchPad { catch_switch: Arc<Value>, arguments: Vec<Arc<Value>> },
+    /// cleanuppad. the parent is `none` if this pad isn't inside another funclet
+    CleanupPad { parent: Arc<Value>, arguments: Vec<Arc<Value>> },
+    // TODO: va_arg
+}
+
+impl Instruction {
+    /// all the values this instruction uses as operands, in the order they're written
+    pub fn operands(&self) -> Vec<&Arc<Value>> {
+        match self {
+            Self::Add { left_hand_side, right_hand_side, .. }
+            | Self::Subtract { left_hand_side, right_hand_side, .. }
+            | Self::Multiply { left_hand_side, right_hand_side, .. }
+            | Self::UnsignedDivide { left_hand_side, right_hand_side, .. }
+            | Self::SignedDivide { left_hand_side, right_hand_side, .. }
+            | Self::UnsignedRemainder { left_hand_side, right_hand_side }
+            | Self::SignedRemainder { left_hand_side, right_hand_side }
+            | Self::ShiftLeft { left_hand_side, right_hand_side, .. }
+            | Self::LogicalShiftRight { left_hand_side, right_hand_side, .. }
+            | Self::ArithmeticShiftRight { left_hand_side, right_hand_side, .. }
+            | Self::And { left_hand_side, right_hand_side }
+            | Self::Or { left_hand_side, right_hand_side, .. }
+            | Self::ExclusiveOr { left_hand_side, right_hand_side }
+            | Self::CompareIntegers { left_hand_side, right_hand_side, .. }
+            | Self::CompareFloats { left_hand_side, right_hand_side, .. }
+            | Self::FloatAdd { left_hand_side, right_hand_side, .. }
+            | Self::FloatSubtract { left_hand_side, right_hand_side, .. }
+            | Self::FloatMultiply { left_hand_side, right_hand_side, .. }
+            | Self::FloatDivide { left_hand_side, right_hand_side, .. }
+            | Self::FloatRemainder { left_hand_side, right_hand_side, .. } => vec![left_hand_side, right_hand_side],
+            Self::ExtractElement { vector, index } => vec![vector, index],
+            Self::InsertElement { vector, value, index } => vec![vector, value, index],
+            Self::ShuffleVector { first, second, mask } => vec![first, second, mask],
+            Self::ExtractValue { aggregate, .. } => vec![aggregate],
+            Self::InsertValue { aggregate, value, .. } => vec![aggregate, value],
+            Self::StackAllocate { num_elements, .. } => num_elements.iter().collect(),
+            Self::Load { pointer, .. } | Self::AtomicLoad { pointer, .. } => vec![pointer],
+            Self::Store { value, pointer, .. } | Self::AtomicStore { value, pointer, .. } => vec![value, pointer],
+            Self::Fence { .. } => vec![],
+            Self::CompareExchange {
+                pointer, compare_value, new_value, ..
+            } => vec![pointer, compare_value, new_value],
+            Self::AtomicReadModifyWrite { pointer, value, .. } => vec![pointer, value],
+            Self::GetElementPointer { pointer, indices, .. } => std::iter::once(pointer).chain(indices).collect(),
+            Self::Truncate { value, .. }
+            | Self::ZeroExtend { value, .. }
+            | Self::SignExtend { value, .. }
+            | Self::FloatTruncate { value, .. }
+            | Self::FloatExtend { value, .. }
+            | Self::FloatToUnsignedInteger { value, .. }
+            | Self::FloatToSignedInteger { value, .. }
+            | Self::UnsignedIntegerToFloat { value, .. }
+            | Self::SignedIntegerToFloat { value, .. }
+            | Self::PointerToInteger { value, .. }
+            | Self::IntegerToPointer { value, .. }
+            | Self::BitCast { value, .. }
+            | Self::AddressSpaceCast { value, .. }
+            | Self::Freeze { value }
+            | Self::FloatNegate { value, .. } => vec![value],
+            Self::Select { condition, true_value, false_value, .. } => vec![condition, true_value, false_value],
+            Self::Phi { incoming, .. } => incoming.iter().flat_map(|i| [&i.value, &i.block]).collect(),
+            Self::Call {
+                callee,
+                function_arguments,
+                operand_bundles,
+                ..
+            } => std::iter::once(callee)
+                .chain(function_arguments.iter().map(|argument| &argument.value))
+                .chain(operand_bundles.iter().flat_map(|bundle| &bundle.inputs))
+                .collect(),
+            Self::CallAssembly { arguments, .. } => arguments.iter().map(|argument| &argument.value).collect(),
+            Self::LandingPad { clauses, .. } => clauses
+                .iter()
+                .map(|clause| match clause {
+                    LandingPadClause::Catch(value) | LandingPadClause::Filter(value) => value,
+                })
+                .collect(),
+            Self::CatchPad { catch_switch: parent, arguments } | Self::CleanupPad { parent, arguments } => std::iter::once(parent).chain(arguments).collect(),
+        }
+    }
+
+    /// whether this instruction is an exception handling pad, which has to be the first non-phi instruction in its block
+    pub fn is_exception_handling_pad(&self) -> bool {
+        matches!(self, Self::LandingPad { .. } | Self::CatchPad { .. } | Self::CleanupPad { .. })
+    }
+
+    /// the type of the value this instruction produces, which is `Type::Void` for instructions that don't produce a value.
+    /// identified types are looked up in `definitions` when needed.
+    /// this only returns `None` if the instruction is malformed, for example if `extractvalue` is given an index that's out of bounds
+    pub fn get_result_type(&self, definitions: &dyn TypeDefinitions) -> Option<Type> {
+        match self {
+            Self::Add { left_hand_side, .. }
+            | Self::Subtract { left_hand_side, .. }
+            | Self::Multiply { left_hand_side, .. }
+            | Self::UnsignedDivide { left_hand_side, .. }
+            | Self::SignedDivide { left_hand_side, .. }
+            | Self::UnsignedRemainder { left_hand_side, .. }
+            | Self::SignedRemainder { left_hand_side, .. }
+            | Self::ShiftLeft { left_hand_side, .. }
+            | Self::LogicalShiftRight { left_hand_side, .. }
+            | Self::ArithmeticShiftRight { left_hand_side, .. }
+            | Self::And { left_hand_side, .. }
+            | Self::Or { left_hand_side, .. }
+            | Self::ExclusiveOr { left_hand_side, .. }
+            | Self::FloatAdd { left_hand_side, .. }
+            | Self::FloatSubtract { left_hand_side, .. }
+            | Self::FloatMultiply { left_hand_side, .. }
+            | Self::FloatDivide { left_hand_side, .. }
+            | Self::FloatRemainder { left_hand_side, .. } => left_hand_side.get_type(definitions),
+            Self::FloatNegate { value, .. } => value.get_type(definitions),
+            Self::ExtractElement { vector, .. } => match vector.get_type(definitions)? {
+                Type::Vector { element_type, .. } => Some(*element_type),
+                _ => None,
+            },
+            Self::InsertElement { vector, .. } => vector.get_type(definitions),
+            Self::ShuffleVector { first, mask, .. } => match (first.get_type(definitions)?, mask.get_type(definitions)?) {
+                (Type::Vector { element_type, .. }, Type::Vector { length, is_scalable, .. }) => Some(Type::Vector { length, element_type, is_scalable }),
+                _ => None,
+            },
+            Self::ExtractValue { aggregate, indices } => {
+                let mut t = aggregate.get_type(definitions)?;
+
+                for index in indices {
+                    t = match definitions.resolve_type(&t)? {
+                        Type::Structure { types, .. } => types.get(*index)?.clone(),
+                        Type::Array { length, element_type } if index < length => element_type.as_ref().clone(),
+                        _ => return None,
+                    };
+                }
+
+                Some(t)
+            }
+            Self::InsertValue { aggregate, .. } => aggregate.get_type(definitions),
+            Self::StackAllocate { address_space, .. } => Some(Type::Pointer {
+                address_space: address_space.clone().unwrap_or(AddressSpace::Numbered(0)),
+            }),
+            Self::Load { result_type, .. } | Self::AtomicLoad { result_type, .. } => Some(result_type.clone()),
+            Self::Store { .. } | Self::AtomicStore { .. } | Self::Fence { .. } => Some(Type::Void),
+            // the loaded value, and whether it was equal to the compared value
+            Self::CompareExchange { compare_value, .. } => Some(Type::Structure {
+                types: vec![compare_value.get_type(definitions)?, Type::Integer { bit_width: 1 }],
+                is_packed: false,
+            }),
+            Self::AtomicReadModifyWrite { value, .. } => value.get_type(definitions),
+            Self::GetElementPointer { pointer, indices, .. } => {
+                let address_space = match pointer.get_type(definitions)? {
+                    Type::Pointer { address_space } => address_space,
+                    Type::Vector { element_type, .. } => match *element_type {
+                        Type::Pointer { address_space } => address_space,
+                        _ => return None,
+                    },
+                    _ => return None,
+                };
+                let result_type = Type::Pointer { address_space };
+
+                // if the pointer or any of the indices are vectors, the result is a vector of pointers
+                for value in std::iter::once(pointer).chain(indices) {
+                    if let Some(Type::Vector { length, is_scalable, .. }) = value.get_type(definitions) {
+                        return Some(Type::Vector {
+                            length,
+                            element_type: Box::new(result_type),
+                            is_scalable,
+                        });
+                    }
+                }
+
+                Some(result_type)
+            }
+            Self::Truncate { new_type, .. }
+            | Self::ZeroExtend { new_type, .. }
+            | Self::SignExtend { new_type, .. }
+            | Self::FloatTruncate { new_type, .. }
+            | Self::FloatExtend { new_type, .. }
+            | Self::FloatToUnsignedInteger { new_type, .. }
+            | Self::FloatToSignedInteger { new_type, .. }
+            | Self::UnsignedIntegerToFloat { new_type, .. }
+            | Self::SignedIntegerToFloat { new_type, .. }
+            | Self::PointerToInteger { new_type, .. }
+            | Self::IntegerToPointer { new_type, .. }
+            | Self::BitCast { new_type, .. }
+            | Self::AddressSpaceCast { new_type, .. } => Some(new_type.clone()),
+            Self::CompareIntegers { left_hand_side, .. } | Self::CompareFloats { left_hand_side, .. } => Some(match left_hand_side.get_type(definitions)? {
+                Type::Vector { length, is_scalable, .. } => Type::Vector {
+                    length,
+                    element_type: Box::new(Type::Integer { bit_width: 1 }),
+                    is_scalable,
+                },
+                _ => Type::Integer { bit_width: 1 },
+            }),
+            Self::Select { true_value, .. } => true_value.get_type(definitions),
+            Self::Phi { value_type, .. } => Some(value_type.clone()),
+            Self::Freeze { value } => value.get_type(definitions),
+            Self::Call { function_type, .. } | Self::CallAssembly { call_type: function_type, .. } => Some(return_type_of(function_type)),
+            Self::LandingPad { result_type, .. } => Some(result_type.clone()),
+            Self::CatchPad { .. } | Self::CleanupPad { .. } => Some(Type::Token),
+        }
+    }
+}
+
+impl Instruction {
+    /// evaluates this instruction at compile time as a constant expression, if all of its operands can be evaluated and folding it is supported.
+    /// so far that's arithmetic, comparisons and casts on integers, `bitcast`s that don't change the type, `select`, `extractelement` and `insertelement`
+    pub fn evaluate(&self, definitions: &dyn TypeDefinitions) -> Option<Constant> {
+        let operands = self.operands().into_iter().map(|operand| operand.evaluate(definitions)).collect::<Option<Vec<_>>>()?;
+
+        // poison spreads to the result of everything but select, which only cares about the value it picks
+        if !matches!(self, Self::Select { .. }) && operands.contains(&Constant::Poison) {
+            return Some(Constant::Poison);
+        }
+
+        let width_of = |value: &Value| match value.get_type(definitions)? {
+            Type::Integer { bit_width } => Some(bit_width),
+            _ => None,
+        };
+
+        match self {
+            Self::Add { left_hand_side, .. }
+            | Self::Subtract { left_hand_side, .. }
+            | Self::Multiply { left_hand_side, .. }
+            | Self::UnsignedDivide { left_hand_side, .. }
+            | Self::SignedDivide { left_hand_side, .. }
+            | Self::UnsignedRemainder { left_hand_side, .. }
+            | Self::SignedRemainder { left_hand_side, .. }
+            | Self::ShiftLeft { left_hand_side, .. }
+            | Self::LogicalShiftRight { left_hand_side, .. }
+            | Self::ArithmeticShiftRight { left_hand_side, .. }
+            | Self::And { left_hand_side, .. }
+            | Self::Or { left_hand_side, .. }
+            | Self::ExclusiveOr { left_hand_side, .. } => {
+                let bit_width = width_of(left_hand_side)?;
+                let (left, right) = (integer_value(&operands[0], bit_width)?, integer_value(&operands[1], bit_width)?);
+
+                Some(fold_integer_binary(self, &left, &right).map_or(Constant::Poison, integer_constant))
+            }
+            Self::CompareIntegers { comparison, left_hand_side, .. } => {
+                let bit_width = width_of(left_hand_side)?;
+                let (left, right) = (integer_value(&operands[0], bit_width)?, integer_value(&operands[1], bit_width)?);
+
+                Some(Constant::Boolean(match comparison {
+                    IntegerComparison::Equal => left == right,
+                    IntegerComparison::NotEqual => left != right,
+                    IntegerComparison::UnsignedGreaterThan => left.unsigned_cmp(&right).is_gt(),
+                    IntegerComparison::UnsignedGreaterOrEqual => left.unsigned_cmp(&right).is_ge(),
+                    IntegerComparison::UnsignedLessThan => left.unsigned_cmp(&right).is_lt(),
+                    IntegerComparison::UnsignedLessOrEqual => left.unsigned_cmp(&right).is_le(),
+                    IntegerComparison::SignedGreaterThan => left.signed_cmp(&right).is_gt(),
+                    IntegerComparison::SignedGreaterOrEqual => left.signed_cmp(&right).is_ge(),
+                    IntegerComparison::SignedLessThan => left.signed_cmp(&right).is_lt(),
+                    IntegerComparison::SignedLessOrEqual => left.signed_cmp(&right).is_le(),
+                }))
+            }
+            Self::Truncate { allowed_wrapping, value, new_type } => {
+                let value = integer_value(&operands[0], width_of(value)?)?;
+                let Type::Integer { bit_width } = new_type else {
+                    return None;
+                };
+                let is_poison = (!allowed_wrapping.can_wrap_unsigned && !value.fits_unsigned(*bit_width)) || (!allowed_wrapping.can_wrap_signed && !value.fits_signed(*bit_width));
+
+                Some(if is_poison { Constant::Poison } else { integer_constant(value.zero_extend(*bit_width)) })
+            }
+            Self::ZeroExtend { value, new_type: Type::Integer { bit_width } } => Some(integer_constant(integer_value(&operands[0], width_of(value)?)?.zero_extend(*bit_width))),
+            Self::SignExtend { value, new_type: Type::Integer { bit_width } } => Some(integer_constant(integer_value(&operands[0], width_of(value)?)?.sign_extend(*bit_width))),
+            Self::BitCast { value, new_type } if value.get_type(definitions).as_ref() == Some(new_type) => Some(operands[0].clone()),
+            Self::Select { .. } => match &operands[0] {
+                Constant::Boolean(condition) => Some(operands[if *condition { 1 } else { 2 }].clone()),
+                Constant::Integer(condition) => Some(operands[if condition.is_zero() { 2 } else { 1 }].clone()),
+                Constant::Poison => Some(Constant::Poison),
+                _ => None,
+            },
+            Self::ExtractElement { index, .. } => {
+                let index = integer_value(&operands[1], width_of(index)?)?;
+
+                match &operands[0] {
+                    Constant::Vector(values) => index.to_u64().and_then(|index| values.get(index as usize)).map_or(Some(Constant::Poison), |element| element.evaluate(definitions)),
+                    Constant::Zero => Some(Constant::Zero),
+                    _ => None,
+                }
+            }
+            Self::InsertElement { value, index, .. } => {
+                let index = integer_value(&operands[2], width_of(index)?)?;
+                let Constant::Vector(values) = &operands[0] else {
+                    return None;
+                };
+
+                let Some(index) = index.to_u64().map(|index| index as usize).filter(|index| *index < values.len()) else {
+                    return Some(Constant::Poison);
+                };
+
+                let mut values = values.clone();
+                values[index] = value.clone();
+                Some(Constant::Vector(values))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// the value of an integer constant with the given bit width, if it's a constant that's known. integers of other widths give `None`, since operands with mismatched types can't be folded
+fn integer_value(constant: &Constant, bit_width: usize) -> Option<Integer> {
+    match constant {
+        Constant::Integer(value) if value.bit_width() == bit_width => Some(value.clone()),
+        Constant::Boolean(value) => Some(Integer::from_u64(bit_width, (*value).into())),
+        Constant::Zero => Some(Integer::zero(bit_width)),
+        _ => None,
+    }
+}
+
+/// makes a constant out of a folded integer, which is a boolean if it only has one bit
+fn integer_constant(value: Integer) -> Constant {
+    if value.bit_width() == 1 {
+        Constant::Boolean(!value.is_zero())
+    } else {
+        Constant::Integer(value)
+    }
+}
+
+/// folds an integer binary operation, returning `None` if the result is poison
+fn fold_integer_binary(instruction: &Instruction, left: &Integer, right: &Integer) -> Option<Integer> {
+    let bit_width = left.bit_width();
+    // results are poison if they wrap around when they aren't allowed to, which is checked by doing the operation with enough bits that it can't wrap
+    let check_wrapping = |allowed_wrapping: &AllowedWrapping, operation: &dyn Fn(&Integer, &Integer) -> Integer| {
+        let wide_width = bit_width * 2 + 1;
+        let unsigned = operation(&left.zero_extend(wide_width), &right.zero_extend(wide_width));
+        let signed = operation(&left.sign_extend(wide_width), &right.sign_extend(wide_width));
+
+        (allowed_wrapping.can_wrap_unsigned || unsigned.fits_unsigned(bit_width)) && (allowed_wrapping.can_wrap_signed || signed.fits_signed(bit_width))
+    };
+    let shift = right.to_u64().filter(|shift| *shift < bit_width as u64).map(|shift| shift as usize);
+    // bits shifted out of the right of a value with `exact` have to be zero
+    let exact_shift = |is_exact: bool| shift.filter(|shift| !is_exact || left.shift_left(bit_width - shift).is_zero());
+    let is_signed_overflow = left == &Integer::signed_minimum(bit_width) && right == &Integer::all_ones(bit_width);
+
+    match instruction {
+        Instruction::Add { allowed_wrapping, .. } => check_wrapping(allowed_wrapping, &Integer::wrapping_add).then(|| left.wrapping_add(right)),
+        Instruction::Subtract { allowed_wrapping, .. } => check_wrapping(allowed_wrapping, &Integer::wrapping_sub).then(|| left.wrapping_sub(right)),
+        Instruction::Multiply { allowed_wrapping, .. } => check_wrapping(allowed_wrapping, &Integer::wrapping_mul).then(|| left.wrapping_mul(right)),
+        Instruction::UnsignedDivide { is_exact, .. } => left.unsigned_divide(right).filter(|(_, remainder)| !is_exact || remainder.is_zero()).map(|(quotient, _)| quotient),
+        Instruction::SignedDivide { is_exact, .. } => left
+            .signed_divide(right)
+            .filter(|(_, remainder)| !is_signed_overflow && (!is_exact || remainder.is_zero()))
+            .map(|(quotient, _)| quotient),
+        Instruction::UnsignedRemainder { .. } => left.unsigned_divide(right).map(|(_, remainder)| remainder),
+        Instruction::SignedRemainder { .. } => left.signed_divide(right).filter(|_| !is_signed_overflow).map(|(_, remainder)| remainder),
+        Instruction::ShiftLeft { allowed_wrapping, .. } => {
+            let shift = shift?;
+            check_wrapping(allowed_wrapping, &|left, _| left.shift_left(shift)).then(|| left.shift_left(shift))
+        }
+        Instruction::LogicalShiftRight { is_exact, .. } => exact_shift(*is_exact).map(|shift| left.logical_shift_right(shift)),
+        Instruction::ArithmeticShiftRight { is_exact, .. } => exact_shift(*is_exact).map(|shift| left.arithmetic_shift_right(shift)),
+        Instruction::And { .. } => Some(left.and(right)),
+        Instruction::Or { disjoint, .. } => (!disjoint || left.and(right).is_zero()).then(|| left.or(right)),
+        Instruction::ExclusiveOr { .. } => Some(left.xor(right)),
+        _ => unreachable!("{instruction:?} isn't an integer binary operation"),
+    }
+}
+
+/// the type of the value returned by calling something with the given type.
+/// the short form of calls to inline assembly only gives the return type instead of the whole function type
+fn return_type_of(function_type: &Type) -> Type {
+    match function_type {
+        Type::Function { return_type, .. } => return_type.as_ref().clone(),
+        t => t.clone(),
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
@@ -216,6 +736,30 @@ pub enum Ordering {
     SequentiallyConsistent,
 }
 
+/// the operation an `atomicrmw` instruction does with the value in memory and its operand, see https://llvm.org/docs/LangRef.html#atomicrmw-instruction
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AtomicOperation {
+    Exchange,
+    Add,
+    Subtract,
+    And,
+    Nand,
+    Or,
+    ExclusiveOr,
+    SignedMax,
+    SignedMin,
+    UnsignedMax,
+    UnsignedMin,
+    FloatAdd,
+    FloatSubtract,
+    FloatMax,
+    FloatMin,
+    /// increments the value, wrapping to zero once it reaches the operand
+    UnsignedIncrementWrap,
+    /// decrements the value, wrapping to the operand once it reaches zero or goes above the operand
+    UnsignedDecrementWrap,
+}
+
 /// https://llvm.org/docs/LangRef.html#id306
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum IntegerComparison {
@@ -231,6 +775,27 @@ pub enum IntegerComparison {
     SignedLessOrEqual,
 }
 
+/// https://llvm.org/docs/LangRef.html#fcmp-instruction. ordered comparisons are false if either operand is a nan, and unordered comparisons are true
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FloatComparison {
+    False,
+    OrderedEqual,
+    OrderedGreaterThan,
+    OrderedGreaterOrEqual,
+    OrderedLessThan,
+    OrderedLessOrEqual,
+    OrderedNotEqual,
+    Ordered,
+    UnorderedEqual,
+    UnorderedGreaterThan,
+    UnorderedGreaterOrEqual,
+    UnorderedLessThan,
+    UnorderedLessOrEqual,
+    UnorderedNotEqual,
+    Unordered,
+    True,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
 pub enum TailCallHint {
     #[default]
@@ -240,8 +805,8 @@ pub enum TailCallHint {
     NeverTail,
 }
 
-/// TODO
-#[derive(Debug, Clone)]
+/// a value that can be used as an operand to an instruction
+#[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     FromInstruction {
         /// the instruction that produced this value
@@ -253,10 +818,6 @@ pub enum Value {
         constant: Constant,
     },
     /// TODO
-    FromGlobal,
-    /// TODO
-    FromFunction,
-    /// TODO
     FromLabel,
     FromIdentifier {
         value_type: Type,
@@ -265,34 +826,64 @@ pub enum Value {
 }
 
 impl Value {
-    pub fn from_type_constant(constant_type: Type, constant: Constant) -> Self {
+    /// makes a value from a constant, converting floating point literals to the format of the type. this fails if the constant can't be a value of the type
+    pub fn from_type_constant(constant_type: Type, constant: Constant) -> Result<Self, &'static str> {
+        let constant = match (constant, &constant_type) {
+            (Constant::FloatingPoint(value), Type::FloatingPoint { kind }) => Constant::FloatingPoint(value.convert(*kind).ok_or("floating point constant invalid for type")?),
+            // integer literals are given bit widths that make this give the right value, and like in LLVM they're truncated if they're too big
+            (Constant::Integer(value), Type::Integer { bit_width }) => Constant::Integer(value.sign_extend(*bit_width)),
+            (constant, _) => constant,
+        };
+
         if !constant.is_compatible_with_type(&constant_type) {
-            panic!("constant {constant:?} is incompatible with type {constant_type:?}");
+            return Err("constant is incompatible with its type");
         }
 
-        Self::FromConstant { constant_type, constant }
+        Ok(Self::FromConstant { constant_type, constant })
     }
 
-    pub fn get_type(&self) -> &Type {
-        todo!()
+    /// the type of this value. identified types are looked up in `definitions` when needed, see `Instruction::get_result_type`
+    pub fn get_type(&self, definitions: &dyn TypeDefinitions) -> Option<Type> {
+        match self {
+            Self::FromInstruction { instruction } => instruction.get_result_type(definitions),
+            Self::FromConstant { constant_type, .. } => Some(constant_type.clone()),
+            Self::FromLabel => Some(Type::Label),
+            Self::FromIdentifier { value_type, .. } => Some(value_type.clone()),
+        }
+    }
+
+    /// evaluates this value at compile time if it's a constant, folding constant expressions into simpler constants. constants inside of structures, arrays and vectors are left as they are.
+    /// this returns `None` if the value isn't a constant, or if it depends on something that's only known later, like the address of a global
+    pub fn evaluate(&self, definitions: &dyn TypeDefinitions) -> Option<Constant> {
+        match self {
+            Self::FromConstant {
+                constant: Constant::Expression(instruction),
+                ..
+            } => instruction.evaluate(definitions),
+            Self::FromConstant { constant, .. } => Some(constant.clone()),
+            _ => None,
+        }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Constant {
     Void,
     Boolean(bool),
-    Integer(usize),
-    FloatingPoint(usize),
+    Integer(Integer),
+    FloatingPoint(FloatingPointValue),
     NullPointer,
     NoneToken,
     Structure(Vec<Arc<Value>>),
     Array(Vec<Arc<Value>>),
     Vector(Vec<Arc<Value>>),
     Zero,
-    Metadata,
+    /// metadata used as a value, which can only be an operand of calls
+    Metadata(Metadata),
     Undefined,
     Poison,
+    /// a constant expression, which is an instruction that only uses constants and globals, see https://llvm.org/docs/LangRef.html#constant-expressions
+    Expression(Box<Instruction>),
 }
 
 impl Constant {
@@ -300,38 +891,236 @@ impl Constant {
         match self {
             Constant::Void => t == &Type::Void,
             Constant::Boolean(_) => t == &Type::Integer { bit_width: 1 },
-            Constant::Integer(_) => matches!(t, Type::Integer { .. }),
-            Constant::FloatingPoint(_) => matches!(t, Type::FloatingPoint { .. }),
+            Constant::Integer(value) => t == &Type::Integer { bit_width: value.bit_width() },
+            Constant::FloatingPoint(value) => matches!(t, Type::FloatingPoint { kind } if *kind == value.kind),
             Constant::NullPointer => matches!(t, Type::Pointer { .. }),
             Constant::NoneToken => t == &Type::Token,
             Constant::Structure(values) => match t {
-                Type::Structure { types, .. } => !values.iter().map(|v| v.get_type()).zip(types).any(|(a, b)| a != b),
+                Type::Structure { types, .. } => values.len() == types.len() && !values.iter().map(|v| v.get_type(&())).zip(types).any(|(a, b)| a.as_ref() != Some(b)),
+                // the definition of an identified type isn't known here, so it has to be checked later
+                Type::Identified { .. } => true,
                 _ => false,
             },
             Constant::Array(values) => match t {
-                Type::Array { length, element_type } => *length == values.len() && !values.iter().any(|v| v.get_type() != element_type.as_ref()),
+                Type::Array { length, element_type } => *length == values.len() && !values.iter().any(|v| v.get_type(&()).as_ref() != Some(element_type.as_ref())),
                 _ => false,
             },
             Constant::Vector(values) => match t {
-                Type::Vector { length, element_type, .. } => *length == values.len() && !values.iter().any(|v| v.get_type() != element_type.as_ref()),
+                Type::Vector { length, element_type, .. } => *length == values.len() && !values.iter().any(|v| v.get_type(&()).as_ref() != Some(element_type.as_ref())),
                 _ => false,
             },
             Constant::Zero => true,
-            Constant::Metadata => t == &Type::Metadata,
+            Constant::Metadata(_) => t == &Type::Metadata,
             Constant::Undefined => !matches!(t, Type::Label | Type::Void),
             Constant::Poison => true,
+            // instructions can only be checked once identified types are known, since they might be needed to find the result type
+            Constant::Expression(instruction) => instruction.get_result_type(&()).is_none_or(|result_type| &result_type == t),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+/// https://llvm.org/docs/LangRef.html#metadata
+#[derive(Debug, Clone, PartialEq)]
+pub enum Metadata {
+    /// a reference to a numbered node, like `!0`
+    Node(usize),
+    /// a metadata string, like `!"wchar_size"`
+    String(String),
+    /// a value used as metadata, like `i32 1` or `ptr %x`
+    Value(Arc<Value>),
+    /// a tuple of other metadata, like `!{i32 1, !"wchar_size", !0}`. `null` operands are `None`
+    Tuple(Vec<Option<Metadata>>),
+    Specialized(SpecializedMetadata),
+}
+
+/// a node with its own syntax, which is mostly used for debug info, like `!DILocation(line: 2, column: 7, scope: !4)`.
+/// see https://llvm.org/docs/LangRef.html#specialized-metadata-nodes
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpecializedMetadata {
+    /// the name of the node without its `!` prefix, like `DILocation`
+    pub kind: String,
+    /// the fields of the node in the order they're written. fields don't have names in nodes that take a list of operands, like `DIExpression`
+    pub fields: Vec<(Option<String>, MetadataField)>,
+}
+
+/// the value of a field in a specialized metadata node
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetadataField {
+    Integer(Integer),
+    Boolean(bool),
+    String(String),
+    /// one or more named constants combined with `|`, like `DW_TAG_member` or `DIFlagPrototyped | DIFlagAllCallsDescribed`
+    Flags(Vec<String>),
+    /// `null` is `None`
+    Metadata(Option<Metadata>),
+}
+
+/// the value of a floating point constant, see https://llvm.org/docs/LangRef.html#simple-constants
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FloatingPointValue {
+    pub kind: FloatingPointKind,
+    /// the bits of the value in the format given by `kind`. `PpcFp128` values keep their high-order double in the low 64 bits, like LLVM does
+    pub bits: u128,
+}
+
+impl FloatingPointValue {
+    pub fn from_f64(value: f64) -> Self {
+        Self {
+            kind: FloatingPointKind::Binary64,
+            bits: value.to_bits().into(),
+        }
+    }
+
+    /// this value as a double, if it's a `float` or a `double`
+    pub fn to_f64(self) -> Option<f64> {
+        match self.kind {
+            FloatingPointKind::Binary64 => Some(f64::from_bits(self.bits as u64)),
+            FloatingPointKind::Binary32 => {
+                let bits = self.bits as u32;
+
+                if f32::from_bits(bits).is_nan() {
+                    // nan payloads are moved to the top of the double's mantissa, which rust doesn't promise to do
+                    let sign = u64::from(bits >> 31) << 63;
+                    Some(f64::from_bits(sign | 0x7ff << 52 | u64::from(bits & 0x7fffff) << 29))
+                } else {
+                    Some(f32::from_bits(bits).into())
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// converts this value to another kind of floating point value, the same way LLVM converts decimal and `0x` literals (which are doubles) to the type they're used as.
+    /// this fails if the value can't be represented exactly, or if this isn't a double and the kinds are different
+    pub fn convert(self, kind: FloatingPointKind) -> Option<Self> {
+        if self.kind == kind {
+            return Some(self);
+        } else if self.kind != FloatingPointKind::Binary64 {
+            return None;
+        }
+
+        let bits = self.bits as u64;
+        let bits = match kind {
+            FloatingPointKind::Binary16 => narrow_double(bits, 5, 10)?,
+            FloatingPointKind::Brain => narrow_double(bits, 8, 7)?,
+            FloatingPointKind::Binary32 => narrow_double(bits, 8, 23)?,
+            FloatingPointKind::Binary64 => unreachable!(),
+            FloatingPointKind::Binary128 => {
+                let (sign, exponent, fraction) = widen_double(bits);
+                sign << 127 | exponent << 112 | fraction
+            }
+            FloatingPointKind::X86Fp80 => {
+                // x87 values store the integer bit explicitly, which is only clear for zero once doubles are widened
+                let (sign, exponent, fraction) = widen_double(bits);
+                sign << 79 | exponent << 64 | u128::from(exponent != 0) << 63 | fraction >> 49
+            }
+            // the low-order double is zero
+            FloatingPointKind::PpcFp128 => bits.into(),
+        };
+
+        Some(Self { kind, bits })
+    }
+}
+
+/// converts the bits of a double to a narrower IEEE-754 format, if the value fits exactly
+fn narrow_double(bits: u64, exponent_bits: u32, mantissa_bits: u32) -> Option<u128> {
+    let sign = u128::from(bits >> 63) << (exponent_bits + mantissa_bits);
+    let exponent = ((bits >> 52) & 0x7ff) as i64;
+    let mantissa = bits & ((1 << 52) - 1);
+    let dropped = 52 - mantissa_bits;
+
+    if exponent == 0x7ff {
+        // infinities and nans fit as long as none of the payload is lost
+        if mantissa & ((1 << dropped) - 1) != 0 {
+            return None;
+        }
+
+        return Some(sign | ((1 << exponent_bits) - 1) << mantissa_bits | u128::from(mantissa >> dropped));
+    } else if exponent == 0 {
+        // subnormal doubles are far too small for any of the narrower formats
+        return (mantissa == 0).then_some(sign);
+    }
+
+    let bias = (1 << (exponent_bits - 1)) - 1;
+    let exponent = exponent - 1023;
+
+    if exponent > bias {
+        return None;
+    }
+
+    // values too small to be normal in the narrower format become subnormal, which loses some more bits
+    let (biased, shift) = if exponent >= 1 - bias {
+        ((exponent + bias) as u128, dropped as i64)
+    } else {
+        (0, dropped as i64 + 1 - bias - exponent)
+    };
+    let significand = mantissa | 1 << 52;
+
+    if shift > 53 || significand & ((1 << shift) - 1) != 0 {
+        return None;
+    }
+
+    Some(sign | biased << mantissa_bits | u128::from(significand >> shift) & ((1 << mantissa_bits) - 1))
+}
+
+/// splits the bits of a double into the sign, biased exponent and 112 bit fraction of an IEEE-754 quad, which can represent every double exactly
+fn widen_double(bits: u64) -> (u128, u128, u128) {
+    let sign = u128::from(bits >> 63);
+    let exponent = (bits >> 52) & 0x7ff;
+    let mantissa = u128::from(bits & ((1 << 52) - 1));
+
+    match exponent {
+        0x7ff => (sign, 0x7fff, mantissa << 60),
+        0 if mantissa == 0 => (sign, 0, 0),
+        0 => {
+            // subnormal doubles are normal quads, so the mantissa has to be normalized
+            let leading = 127 - mantissa.leading_zeros();
+            (sign, u128::from(leading) + 16383 - 1074, (mantissa ^ 1 << leading) << (112 - leading))
+        }
+        _ => (sign, u128::from(exponent) + 16383 - 1023, mantissa << 60),
+    }
+}
+
+/// a value a phi node takes when control comes from a specific block
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncomingValue {
+    pub value: Arc<Value>,
+    /// the label of the predecessor block
+    pub block: Arc<Value>,
+}
+
+/// an argument passed to a function by a call, along with the parameter attributes it's passed with
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallArgument {
+    pub value: Arc<Value>,
+    pub attributes: Vec<ParameterAttribute>,
+}
+
+/// values passed along with a call for something other than the function being called, like `"deopt"(i32 1)`, see https://llvm.org/docs/LangRef.html#operand-bundles
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperandBundle {
+    /// what the bundle is for, like `deopt` or `funclet`
+    pub tag: String,
+    pub inputs: Vec<Arc<Value>>,
+}
+
+/// decides which exceptions a `landingpad` catches, see https://llvm.org/docs/LangRef.html#landingpad-instruction
+#[derive(Debug, Clone, PartialEq)]
+pub enum LandingPadClause {
+    /// catch, which catches exceptions of the type the value identifies
+    Catch(Arc<Value>),
+    /// filter, which catches exceptions that don't match any of the types in an array
+    Filter(Arc<Value>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct SwitchDestination {
     pub value: Arc<Value>,
     pub destination: Arc<Value>,
 }
 
 /// https://llvm.org/docs/LangRef.html#terminator-instructions
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Terminator {
     Return {
         value: Arc<Value>,
@@ -353,6 +1142,111 @@ pub enum Terminator {
         address: Arc<Value>,
         valid_destinations: Vec<Arc<Value>>,
     },
-    // TODO: invoke, callbr, resume, catchswitch, catchret, cleanupret
+    /// invoke, which calls a function like `call` but branches to `unwind_destination` if the function unwinds
+    Invoke {
+        calling_convention: Option<String>,
+        return_value_attributes: Vec<ParameterAttribute>,
+        address_space: Option<AddressSpace>,
+        /// the type of the function being called, which is given a function type like `call` when only the return type is written
+        function_type: Type,
+        callee: Arc<Value>,
+        function_arguments: Vec<CallArgument>,
+        function_attributes: Vec<FunctionAttribute>,
+        attribute_groups: Vec<usize>,
+        operand_bundles: Vec<OperandBundle>,
+        normal_destination: Arc<Value>,
+        unwind_destination: Arc<Value>,
+    },
+    /// callbr, which is only used for `asm goto`
+    CallBranch {
+        return_value_attributes: Vec<ParameterAttribute>,
+        call_type: Type,
+        hints: AssemblyCallHints,
+        template: String,
+        operand_constraints: String,
+        arguments: Vec<CallArgument>,
+        function_attributes: Vec<FunctionAttribute>,
+        attribute_groups: Vec<usize>,
+        fallthrough_destination: Arc<Value>,
+        indirect_destinations: Vec<Arc<Value>>,
+    },
+    /// resume
+    Resume {
+        value: Arc<Value>,
+    },
+    /// catchswitch. the unwind destination is `None` if it unwinds to the caller
+    CatchSwitch {
+        parent: Arc<Value>,
+        handlers: Vec<Arc<Value>>,
+        unwind_destination: Option<Arc<Value>>,
+    },
+    /// catchret
+    CatchReturn {
+        catch_pad: Arc<Value>,
+        destination: Arc<Value>,
+    },
+    /// cleanupret. the unwind destination is `None` if it unwinds to the caller
+    CleanupReturn {
+        cleanup_pad: Arc<Value>,
+        unwind_destination: Option<Arc<Value>>,
+    },
     Unreachable,
 }
+
+impl Terminator {
+    /// all the values this terminator uses as operands (including labels), in the order they're written
+    pub fn operands(&self) -> Vec<&Arc<Value>> {
+        match self {
+            Self::Return { value } => vec![value],
+            Self::ConditionalBranch { condition, if_true, if_false } => vec![condition, if_true, if_false],
+            Self::Branch { destination } => vec![destination],
+            Self::Switch {
+                value,
+                default_destination,
+                destinations,
+            } => [value, default_destination].into_iter().chain(destinations.iter().flat_map(|d| [&d.value, &d.destination])).collect(),
+            Self::IndirectBranch { address, valid_destinations } => std::iter::once(address).chain(valid_destinations).collect(),
+            Self::Invoke {
+                callee,
+                function_arguments,
+                operand_bundles,
+                normal_destination,
+                unwind_destination,
+                ..
+            } => std::iter::once(callee)
+                .chain(function_arguments.iter().map(|argument| &argument.value))
+                .chain(operand_bundles.iter().flat_map(|bundle| &bundle.inputs))
+                .chain([normal_destination, unwind_destination])
+                .collect(),
+            Self::CallBranch {
+                arguments,
+                fallthrough_destination,
+                indirect_destinations,
+                ..
+            } => arguments
+                .iter()
+                .map(|argument| &argument.value)
+                .chain(std::iter::once(fallthrough_destination))
+                .chain(indirect_destinations)
+                .collect(),
+            Self::Resume { value } => vec![value],
+            Self::CatchSwitch {
+                parent,
+                handlers,
+                unwind_destination,
+            } => std::iter::once(parent).chain(handlers).chain(unwind_destination).collect(),
+            Self::CatchReturn { catch_pad, destination } => vec![catch_pad, destination],
+            Self::CleanupReturn { cleanup_pad, unwind_destination } => std::iter::once(cleanup_pad).chain(unwind_destination).collect(),
+            Self::Unreachable => vec![],
+        }
+    }
+
+    /// the type of the value this terminator produces, which is `Type::Void` for terminators that don't produce a value
+    pub fn get_result_type(&self) -> Type {
+        match self {
+            Self::Invoke { function_type, .. } | Self::CallBranch { call_type: function_type, .. } => return_type_of(function_type),
+            Self::CatchSwitch { .. } => Type::Token,
+            _ => Type::Void,
+        }
+    }
+}
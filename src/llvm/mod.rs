@@ -1,32 +1,182 @@
 use lalrpop_util::lalrpop_mod;
 
-lalrpop_mod!(pub grammar, "/llvm/grammar.rs");
+lalrpop_mod!(
+    #[allow(clippy::all)]
+    pub grammar,
+    "/llvm/grammar.rs"
+);
 
+mod printer;
 #[cfg(test)]
 pub mod test;
 
 /// because lalrpop is broken
 type DualValue = [std::sync::Arc<crate::ir::Value>; 2];
 
-pub fn parse_escape_sequences(s: &str) -> String {
-    // TODO
-    s.to_string()
+/// decodes the escape sequences in a string literal, which are `\\` for a backslash and a backslash followed by two hexadecimal digits for any other byte.
+/// backslashes that don't start an escape sequence are kept as they are, like in LLVM
+pub fn parse_escape_sequences(s: &str) -> Vec<u8> {
+    let mut rest = s.as_bytes();
+    let mut result = Vec::with_capacity(s.len());
+
+    while let [byte, tail @ ..] = rest {
+        rest = match (byte, tail) {
+            (b'\\', [b'\\', tail @ ..]) => {
+                result.push(b'\\');
+                tail
+            }
+            (b'\\', [high, low, tail @ ..]) if high.is_ascii_hexdigit() && low.is_ascii_hexdigit() => {
+                result.push(u8::from_str_radix(std::str::from_utf8(&[*high, *low]).unwrap(), 16).unwrap());
+                tail
+            }
+            _ => {
+                result.push(*byte);
+                tail
+            }
+        };
+    }
+
+    result
+}
+
+/// parses a hexadecimal floating point literal. the letter after `0x` gives the format its bits are in, and if there isn't one it's a double
+pub fn parse_hexadecimal_float(s: &str) -> Result<crate::ir::FloatingPointValue, &'static str> {
+    use crate::types::FloatingPointKind;
+
+    let (kind, width, digits) = match s.as_bytes()[2] {
+        b'K' => (FloatingPointKind::X86Fp80, 80, &s[3..]),
+        b'L' => (FloatingPointKind::Binary128, 128, &s[3..]),
+        b'M' => (FloatingPointKind::PpcFp128, 128, &s[3..]),
+        b'H' => (FloatingPointKind::Binary16, 16, &s[3..]),
+        b'R' => (FloatingPointKind::Brain, 16, &s[3..]),
+        _ => (FloatingPointKind::Binary64, 64, &s[2..]),
+    };
+    let too_large = "hexadecimal floating point constant is too large for its type";
+    let parse = |digits: &str| if digits.is_empty() { Ok(0) } else { u128::from_str_radix(digits, 16).map_err(|_| too_large) };
+
+    let bits = match kind {
+        // these are written as two 64 bit halves with the low half first, and the low half is only there if there are at least 16 digits
+        FloatingPointKind::Binary128 | FloatingPointKind::PpcFp128 => {
+            let (low, high) = if digits.len() >= 16 { digits.split_at(16) } else { ("", digits) };
+            let high = parse(high)?;
+
+            if high >> 64 != 0 {
+                return Err(too_large);
+            }
+
+            high << 64 | parse(low)?
+        }
+        _ => parse(digits)?,
+    };
+
+    if width < 128 && bits >> width != 0 {
+        return Err(too_large);
+    }
+
+    Ok(crate::ir::FloatingPointValue { kind, bits })
+}
+
+/// parses an integer literal, giving it a bit width that makes sign extending or truncating it to the type it's used as give the right value.
+/// decimal and `u0x` literals get an extra bit so they stay positive, and the most significant bit that's set in an `s0x` literal is its sign bit, like in LLVM
+pub fn parse_integer_literal(s: &str) -> crate::integer::Integer {
+    use crate::integer::Integer;
+
+    let unsigned = |digits: &str, radix| {
+        let value = Integer::parse_unsigned(digits, radix).unwrap();
+        value.zero_extend(value.bit_width() + 1)
+    };
+
+    if let Some(digits) = s.strip_prefix("s0x") {
+        Integer::parse_unsigned(digits, 16).unwrap()
+    } else if let Some(digits) = s.strip_prefix("u0x") {
+        unsigned(digits, 16)
+    } else if let Some(digits) = s.strip_prefix('-') {
+        unsigned(digits, 10).wrapping_neg()
+    } else {
+        unsigned(s, 10)
+    }
+}
+
+/// a value that's written without its type, since it's given once for several values
+enum UntypedValue {
+    Identifier(String),
+    Constant(crate::ir::Constant),
 }
 
-#[derive(Debug)]
+/// something parsed along with the byte offsets of where it starts and ends in the source, for errors about it
+type Located<T> = (usize, T, usize);
+
+/// makes an error about the source between `start` and `end` while parsing
+fn grammar_error<T>(start: usize, end: usize, message: &'static str) -> lalrpop_util::ParseError<usize, T, crate::error::GrammarError> {
+    lalrpop_util::ParseError::User {
+        error: crate::error::GrammarError { message, span: (start, end) },
+    }
+}
+
+/// gives a value that was written without its type a type while parsing
+fn typed_value<T>(value_type: crate::types::Type, (start, value, end): Located<UntypedValue>) -> Result<std::sync::Arc<crate::ir::Value>, lalrpop_util::ParseError<usize, T, crate::error::GrammarError>> {
+    match value {
+        UntypedValue::Identifier(identifier) => Ok(crate::ir::Value::FromIdentifier { value_type, identifier }.into()),
+        UntypedValue::Constant(constant) => typed_constant(value_type, (start, constant, end)),
+    }
+}
+
+/// makes a constant value while parsing, turning constants that don't fit their type into errors
+fn typed_constant<T>(constant_type: crate::types::Type, (start, constant, end): Located<crate::ir::Constant>) -> Result<std::sync::Arc<crate::ir::Value>, lalrpop_util::ParseError<usize, T, crate::error::GrammarError>> {
+    crate::ir::Value::from_type_constant(constant_type, constant).map(Into::into).map_err(|error| grammar_error(start, end, error))
+}
+
+/// metadata attached to an instruction, function or global variable, like `!dbg !5`
+#[derive(Debug, PartialEq)]
+pub struct MetadataAttachment {
+    /// the kind of metadata this is, including its `!` prefix
+    pub kind: String,
+    pub node: crate::ir::Metadata,
+}
+
+/// the type of the function a call calls. calls can be written with only the return type, in which case the function type is made from the types of the arguments
+fn call_function_type(written_type: crate::types::Type, arguments: &[crate::ir::CallArgument]) -> crate::types::Type {
+    match written_type {
+        crate::types::Type::Function { .. } => written_type,
+        return_type => crate::types::Type::Function {
+            return_type: Box::new(return_type),
+            // arguments are always identifiers or constants, which always have a type
+            parameters: arguments.iter().map(|argument| argument.value.get_type(&()).unwrap()).collect(),
+            has_varargs: false,
+        },
+    }
+}
+
+/// gives the callee of a call its type, which is a pointer in the address space the call gives, if any
+fn typed_callee<T>(address_space: &Option<crate::types::AddressSpace>, callee: Located<UntypedValue>) -> Result<std::sync::Arc<crate::ir::Value>, lalrpop_util::ParseError<usize, T, crate::error::GrammarError>> {
+    let address_space = address_space.clone().unwrap_or(crate::types::AddressSpace::Numbered(0));
+    typed_value(crate::types::Type::Pointer { address_space }, callee)
+}
+
+#[derive(Debug, PartialEq)]
 pub enum Operation {
-    Assignment { identifier: String, value: crate::ir::Instruction },
-    NoAssignment { instruction: crate::ir::Instruction },
+    Assignment {
+        identifier: String,
+        value: crate::ir::Instruction,
+        metadata: Vec<MetadataAttachment>,
+    },
+    NoAssignment {
+        instruction: crate::ir::Instruction,
+        metadata: Vec<MetadataAttachment>,
+    },
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct BasicBlock {
     pub name: Option<String>,
     pub operations: Vec<Operation>,
+    /// the name of the value the terminator produces, for terminators like `invoke` that can produce one
+    pub terminator_identifier: Option<String>,
     pub terminator: crate::ir::Terminator,
+    pub terminator_metadata: Vec<MetadataAttachment>,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq)]
 /// https://llvm.org/docs/LangRef.html#linkage
 pub enum LinkageType {
     /// private
@@ -54,7 +204,7 @@ pub enum LinkageType {
     External,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq)]
 /// https://llvm.org/docs/LangRef.html#runtime-preemption-model
 pub enum PreemptionSpecifier {
     /// dso_preemptable
@@ -64,7 +214,7 @@ pub enum PreemptionSpecifier {
     Local,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq)]
 pub enum Visibility {
     /// default
     #[default]
@@ -75,31 +225,372 @@ pub enum Visibility {
     Protected,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default, PartialEq)]
+/// https://llvm.org/docs/LangRef.html#dll-storage-classes
+pub enum DllStorageClass {
+    /// dllimport
+    Import,
+    /// dllexport
+    Export,
+    /// no DLL storage class specified
+    #[default]
+    Default,
+}
+
+#[derive(Debug, Default, PartialEq)]
+/// whether the address of a global value is significant, see https://llvm.org/docs/LangRef.html#global-variables
+pub enum UnnamedAddress {
+    /// the address is significant
+    #[default]
+    Named,
+    /// unnamed_addr
+    Global,
+    /// local_unnamed_addr
+    Local,
+}
+
+#[derive(Debug, PartialEq)]
+/// https://llvm.org/docs/LangRef.html#comdats
+pub enum ComdatSelectionKind {
+    /// any
+    Any,
+    /// exactmatch
+    ExactMatch,
+    /// largest
+    Largest,
+    /// nodeduplicate
+    NoDeduplicate,
+    /// samesize
+    SameSize,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Comdat {
+    pub name: String,
+    pub selection_kind: ComdatSelectionKind,
+}
+
+#[derive(Debug, PartialEq)]
 pub struct FunctionParameter {
     pub parameter_type: crate::types::Type,
+    pub attributes: Vec<crate::types::ParameterAttribute>,
+    /// the name of this parameter. if this isn't specified, the parameter is implicitly numbered
+    pub name: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+/// https://llvm.org/docs/LangRef.html#thread-local-storage-models
+pub enum ThreadLocalMode {
+    /// thread_local
+    GeneralDynamic,
+    /// localdynamic
+    LocalDynamic,
+    /// initialexec
+    InitialExec,
+    /// localexec
+    LocalExec,
+}
+
+/// https://llvm.org/docs/LangRef.html#global-variables
+#[derive(Debug, PartialEq)]
+pub struct GlobalVariable {
     pub name: String,
+    pub linkage: LinkageType,
+    pub preemption_specifier: PreemptionSpecifier,
+    pub visibility: Visibility,
+    pub dll_storage_class: DllStorageClass,
+    pub thread_local: Option<ThreadLocalMode>,
+    pub unnamed_address: UnnamedAddress,
+    pub address_space: Option<crate::types::AddressSpace>,
+    /// externally_initialized
+    pub is_externally_initialized: bool,
+    /// whether this global was defined with `constant` instead of `global`, meaning its contents will never be modified
+    pub is_constant: bool,
+    /// the type of the value stored in this global. the global itself is always a pointer
+    pub value_type: crate::types::Type,
+    /// the initial value of this global. this is only missing for globals defined outside this module
+    pub initializer: Option<std::sync::Arc<crate::ir::Value>>,
+    pub section_name: Option<String>,
+    pub partition_name: Option<String>,
+    /// the name of the comdat this global is in, if any. a bare `comdat` is given the name of the global
+    pub comdat: Option<String>,
+    /// align
+    pub alignment: Option<usize>,
+    /// code_model
+    pub code_model: Option<String>,
+    pub metadata: Vec<MetadataAttachment>,
+    // TODO: sanitizer attributes
 }
 
-#[derive(Debug)]
-pub struct Function {
+/// one of the comma separated properties at the end of a global variable definition, used while parsing
+enum GlobalVariableProperty {
+    Section(String),
+    Partition(String),
+    Comdat(Option<String>),
+    Alignment(usize),
+    CodeModel(String),
+    Metadata(MetadataAttachment),
+}
+
+impl GlobalVariable {
+    fn set_properties(mut self, properties: Vec<GlobalVariableProperty>) -> Self {
+        for property in properties {
+            match property {
+                GlobalVariableProperty::Section(name) => self.section_name = Some(name),
+                GlobalVariableProperty::Partition(name) => self.partition_name = Some(name),
+                GlobalVariableProperty::Comdat(name) => self.comdat = Some(name.unwrap_or_else(|| format!("${}", &self.name[1..]))),
+                GlobalVariableProperty::Alignment(alignment) => self.alignment = Some(alignment),
+                GlobalVariableProperty::CodeModel(model) => self.code_model = Some(model),
+                GlobalVariableProperty::Metadata(attachment) => self.metadata.push(attachment),
+            }
+        }
+
+        self
+    }
+}
+
+/// everything about a function except for its body. this is shared between function declarations and definitions
+#[derive(Debug, PartialEq)]
+pub struct FunctionHeader {
     pub linkage: LinkageType,
     pub preemption_specifier: PreemptionSpecifier,
     pub visibility: Visibility,
-    // TODO: calling convention, DLL storage class, unnamed_addr
+    pub dll_storage_class: DllStorageClass,
+    pub calling_convention: Option<String>,
     pub return_type: crate::types::Type,
     pub return_type_parameter_attributes: Vec<crate::types::ParameterAttribute>,
     pub name: String,
     pub arguments: Vec<FunctionParameter>,
+    /// whether the function has C varargs
+    pub has_varargs: bool,
+    pub unnamed_address: UnnamedAddress,
     pub address_space: Option<crate::types::AddressSpace>,
-    // TODO: function attributes
+    /// function attributes written directly on this function
+    pub attributes: Vec<crate::types::FunctionAttribute>,
+    /// the IDs of the attribute groups (`#0`) this function references
+    pub attribute_groups: Vec<usize>,
     pub section_name: Option<String>,
     pub partition_name: Option<String>,
-    // TODO: comdat
+    /// the name of the comdat this function is in, if any. a bare `comdat` is given the name of the function
+    pub comdat: Option<String>,
     /// align
     pub alignment: Option<usize>,
     /// gc
-    pub is_garbage_collected: bool,
-    // TODO: prefix, prologue, personality, metadata
+    pub garbage_collector: Option<String>,
+    /// prefix
+    pub prefix: Option<std::sync::Arc<crate::ir::Value>>,
+    /// prologue
+    pub prologue: Option<std::sync::Arc<crate::ir::Value>>,
+    /// personality
+    pub personality: Option<std::sync::Arc<crate::ir::Value>>,
+    /// metadata attached to this function. declarations write this after `declare` and definitions write it before their body
+    pub metadata: Vec<MetadataAttachment>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Function {
+    pub header: FunctionHeader,
     pub basic_blocks: Vec<BasicBlock>,
 }
+
+/// a named global value in a module
+#[derive(Debug, PartialEq)]
+pub enum Symbol {
+    /// a function without a body, declared with `declare`
+    FunctionDeclaration(FunctionHeader),
+    /// a function with a body, defined with `define`
+    Function(Function),
+    GlobalVariable(GlobalVariable),
+}
+
+impl Symbol {
+    /// the name of this symbol, including its `@` prefix
+    pub fn name(&self) -> &str {
+        match self {
+            Self::FunctionDeclaration(header) => &header.name,
+            Self::Function(function) => &function.header.name,
+            Self::GlobalVariable(variable) => &variable.name,
+        }
+    }
+
+    /// the address space this symbol is in, which pointers to it are in too
+    pub fn address_space(&self) -> crate::types::AddressSpace {
+        let address_space = match self {
+            Self::FunctionDeclaration(header) => &header.address_space,
+            Self::Function(function) => &function.header.address_space,
+            Self::GlobalVariable(variable) => &variable.address_space,
+        };
+
+        address_space.clone().unwrap_or(crate::types::AddressSpace::Numbered(0))
+    }
+
+    /// the header of this symbol if it's a function, regardless of whether it's declared or defined
+    pub fn function_header(&self) -> Option<&FunctionHeader> {
+        match self {
+            Self::FunctionDeclaration(header) => Some(header),
+            Self::Function(function) => Some(&function.header),
+            Self::GlobalVariable(_) => None,
+        }
+    }
+}
+
+/// a named structure type definition, like `%struct.inode = type { i32, ptr }`
+#[derive(Debug, PartialEq)]
+pub struct TypeDefinition {
+    /// the name of this type, including its `%` prefix
+    pub name: String,
+    /// the type this name refers to. this is usually a structure or opaque structure
+    pub definition: crate::types::Type,
+}
+
+/// a set of function attributes that functions and calls can refer to by ID, like `attributes #0 = { nounwind }`
+#[derive(Debug, PartialEq)]
+pub struct AttributeGroup {
+    pub id: usize,
+    pub attributes: Vec<crate::types::FunctionAttribute>,
+}
+
+/// a numbered metadata node, like `!0 = distinct !{!1}`
+#[derive(Debug, PartialEq)]
+pub struct MetadataDefinition {
+    pub id: usize,
+    /// whether this node is distinct, meaning it's never merged with identical nodes
+    pub is_distinct: bool,
+    /// this is always a tuple or a specialized node
+    pub node: crate::ir::Metadata,
+}
+
+/// metadata with a name, which is a list of numbered nodes, like `!llvm.module.flags = !{!0, !1}`
+#[derive(Debug, PartialEq)]
+pub struct NamedMetadata {
+    /// the name of this metadata, including its `!` prefix
+    pub name: String,
+    /// the IDs of the nodes in this metadata
+    pub nodes: Vec<usize>,
+}
+
+/// a single top-level entity in a module, used while parsing
+enum ModuleItem {
+    SourceFilename(String),
+    DataLayout(String),
+    TargetTriple(String),
+    Comdat(Comdat),
+    TypeDefinition(TypeDefinition),
+    AttributeGroup(AttributeGroup),
+    NamedMetadata(NamedMetadata),
+    MetadataDefinition(MetadataDefinition),
+    Symbol(Box<Symbol>),
+}
+
+/// an LLVM module, corresponding to a single `.ll` file
+#[derive(Debug, Default, PartialEq)]
+pub struct Module {
+    /// source_filename
+    pub source_filename: Option<String>,
+    /// target datalayout
+    pub data_layout: Option<String>,
+    /// target triple
+    pub target_triple: Option<String>,
+    pub comdats: Vec<Comdat>,
+    /// all the named types defined in this module
+    pub type_definitions: Vec<TypeDefinition>,
+    pub attribute_groups: Vec<AttributeGroup>,
+    pub named_metadata: Vec<NamedMetadata>,
+    /// all the numbered metadata nodes defined in this module
+    pub metadata_definitions: Vec<MetadataDefinition>,
+    /// all the functions and global variables in this module, in the order they were declared or defined
+    pub symbols: Vec<Symbol>,
+}
+
+impl Module {
+    fn from_items<T>(items: Vec<Located<ModuleItem>>) -> Result<Self, lalrpop_util::ParseError<usize, T, crate::error::GrammarError>> {
+        let mut module = Self::default();
+
+        for (start, item, end) in items {
+            match item {
+                ModuleItem::SourceFilename(name) => module.source_filename = Some(name),
+                ModuleItem::DataLayout(layout) => module.data_layout = Some(layout),
+                ModuleItem::TargetTriple(triple) => module.target_triple = Some(triple),
+                ModuleItem::Comdat(comdat) => module.comdats.push(comdat),
+                ModuleItem::TypeDefinition(definition) => {
+                    if crate::types::TypeDefinitions::get_type_definition(&module, &definition.name).is_some() {
+                        return Err(grammar_error(start, end, "invalid redefinition of type"));
+                    }
+
+                    module.type_definitions.push(definition);
+                }
+                ModuleItem::AttributeGroup(group) => {
+                    if module.get_attribute_group(group.id).is_some() {
+                        return Err(grammar_error(start, end, "invalid redefinition of attribute group"));
+                    }
+
+                    module.attribute_groups.push(group);
+                }
+                // like in LLVM, defining named metadata again adds to the nodes it already has
+                ModuleItem::NamedMetadata(metadata) => match module.named_metadata.iter_mut().find(|existing| existing.name == metadata.name) {
+                    Some(existing) => existing.nodes.extend(metadata.nodes),
+                    None => module.named_metadata.push(metadata),
+                },
+                ModuleItem::MetadataDefinition(definition) => {
+                    if module.get_metadata_node(definition.id).is_some() {
+                        return Err(grammar_error(start, end, "invalid redefinition of metadata node"));
+                    }
+
+                    module.metadata_definitions.push(definition);
+                }
+                ModuleItem::Symbol(symbol) => {
+                    if module.get_symbol(symbol.name()).is_some() {
+                        return Err(grammar_error(start, end, "invalid redefinition of symbol"));
+                    }
+
+                    module.symbols.push(*symbol);
+                }
+            }
+        }
+
+        Ok(module)
+    }
+
+    /// looks up a symbol by its name (including its `@` prefix)
+    pub fn get_symbol(&self, name: &str) -> Option<&Symbol> {
+        self.symbols.iter().find(|symbol| symbol.name() == name)
+    }
+
+    /// looks up an attribute group by its ID
+    pub fn get_attribute_group(&self, id: usize) -> Option<&AttributeGroup> {
+        self.attribute_groups.iter().find(|group| group.id == id)
+    }
+
+    /// looks up a numbered metadata node by its ID
+    pub fn get_metadata_node(&self, id: usize) -> Option<&MetadataDefinition> {
+        self.metadata_definitions.iter().find(|definition| definition.id == id)
+    }
+
+    /// every function attribute that applies to a function or call, both the ones written directly on it and the ones in the attribute groups it references.
+    /// attribute groups that aren't defined are skipped
+    pub fn function_attributes<'a>(&'a self, attributes: &'a [crate::types::FunctionAttribute], attribute_groups: &'a [usize]) -> impl Iterator<Item = &'a crate::types::FunctionAttribute> {
+        attributes.iter().chain(attribute_groups.iter().filter_map(|id| self.get_attribute_group(*id)).flat_map(|group| &group.attributes))
+    }
+
+    /// iterates over all the functions defined (not just declared) in this module
+    pub fn functions(&self) -> impl Iterator<Item = &Function> {
+        self.symbols.iter().filter_map(|symbol| match symbol {
+            Symbol::Function(function) => Some(function),
+            _ => None,
+        })
+    }
+
+    /// iterates over all the global variables in this module
+    pub fn global_variables(&self) -> impl Iterator<Item = &GlobalVariable> {
+        self.symbols.iter().filter_map(|symbol| match symbol {
+            Symbol::GlobalVariable(variable) => Some(variable),
+            _ => None,
+        })
+    }
+}
+
+impl crate::types::TypeDefinitions for Module {
+    fn get_type_definition(&self, name: &str) -> Option<&crate::types::Type> {
+        self.type_definitions.iter().find(|definition| definition.name == name).map(|definition| &definition.definition)
+    }
+}
@@ -0,0 +1,1727 @@
+use super::{
+    AttributeGroup, BasicBlock, Comdat, ComdatSelectionKind, DllStorageClass, Function, FunctionHeader, FunctionParameter, GlobalVariable, LinkageType, MetadataAttachment, MetadataDefinition, Module, NamedMetadata,
+    Operation, PreemptionSpecifier, Symbol, ThreadLocalMode, TypeDefinition, UnnamedAddress, Visibility,
+};
+use crate::{
+    integer::Integer,
+    ir::{
+        AllowedWrapping, AssemblyCallHints, AtomicOperation, CallArgument, Constant, FastMathFlags, FloatComparison, FloatingPointValue, GetPointerKind, Instruction, IntegerComparison, LandingPadClause, Metadata,
+        MetadataField, OperandBundle, Ordering, SpecializedMetadata, TailCallHint, Terminator, Value,
+    },
+    types::{AddressSpace, CaptureComponents, FloatingPointClasses, FloatingPointKind, FunctionAttribute, MemoryAccess, MemoryLocation, ParameterAttribute, TargetExtensionParameter, Type},
+};
+use std::{
+    fmt::{self, Display, Formatter, Write},
+    sync::Arc,
+};
+
+/// writes a list of items separated by `separator`
+fn write_list<T: Display>(f: &mut Formatter<'_>, items: impl IntoIterator<Item = T>, separator: &str) -> fmt::Result {
+    for (index, item) in items.into_iter().enumerate() {
+        if index > 0 {
+            f.write_str(separator)?;
+        }
+
+        write!(f, "{item}")?;
+    }
+
+    Ok(())
+}
+
+/// writes bytes the way they're written inside a string literal, escaping anything that isn't printable ASCII as a backslash and two hexadecimal digits
+fn write_escaped(f: &mut Formatter<'_>, bytes: impl IntoIterator<Item = u8>) -> fmt::Result {
+    for byte in bytes {
+        match byte {
+            b' '..=b'~' if byte != b'"' && byte != b'\\' => f.write_char(byte.into())?,
+            _ => write!(f, "\\{byte:02X}")?,
+        }
+    }
+
+    Ok(())
+}
+
+/// writes a string literal, escaping anything that can't be written as-is
+fn write_string(f: &mut Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    write_escaped(f, s.bytes())?;
+    f.write_str("\"")
+}
+
+/// a local or global identifier, including its sigil. identifiers that can't be written as-is are quoted
+struct Identifier<'a>(&'a str);
+
+impl Display for Identifier<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (sigil, name) = self.0.split_at(1);
+        let is_named = name.starts_with(|c: char| c.is_ascii_alphabetic() || "-$._\\".contains(c)) && name.chars().all(|c| c.is_ascii_alphanumeric() || "-$._\\".contains(c));
+        let is_numbered = !name.is_empty() && name.chars().all(|c| c.is_ascii_digit());
+
+        if is_named || is_numbered {
+            f.write_str(self.0)
+        } else {
+            f.write_str(sigil)?;
+            write_string(f, name)
+        }
+    }
+}
+
+/// a value written without its type, like the second operand of a binary operation
+struct Operand<'a>(&'a Value);
+
+impl Display for Operand<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Value::FromConstant { constant_type, constant } => ConstantWithType(constant, constant_type).fmt(f),
+            Value::FromIdentifier { identifier, .. } => Identifier(identifier).fmt(f),
+            Value::FromInstruction { instruction } => write!(f, "({instruction})"),
+            // this doesn't have any syntax of its own
+            Value::FromLabel => Ok(()),
+        }
+    }
+}
+
+/// a constant along with the type it's being used as, since the type decides how some constants are written
+struct ConstantWithType<'a>(&'a Constant, &'a Type);
+
+impl Display for ConstantWithType<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let Self(constant, constant_type) = *self;
+
+        match constant {
+            Constant::Void => f.write_str("void"),
+            Constant::Boolean(value) => write!(f, "{value}"),
+            Constant::Integer(value) if value.bit_width() == 1 => write!(f, "{}", !value.is_zero()),
+            Constant::Integer(value) => value.fmt(f),
+            Constant::FloatingPoint(value) => write!(f, "{value}"),
+            Constant::NullPointer => f.write_str("null"),
+            Constant::NoneToken => f.write_str("none"),
+            Constant::Structure(values) => {
+                f.write_str("{ ")?;
+                write_list(f, values, ", ")?;
+                f.write_str(" }")
+            }
+            Constant::Array(values) => {
+                let is_string = matches!(constant_type, Type::Array { element_type, .. } if **element_type == Type::Integer { bit_width: 8 });
+                let bytes = values
+                    .iter()
+                    .map(|value| match value.as_ref() {
+                        Value::FromConstant {
+                            constant: Constant::Integer(byte), ..
+                        } => byte.to_u64().and_then(|byte| u8::try_from(byte).ok()),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>();
+
+                match bytes {
+                    Some(bytes) if is_string => {
+                        f.write_str("c\"")?;
+                        write_escaped(f, bytes)?;
+                        f.write_str("\"")
+                    }
+                    _ => {
+                        f.write_str("[")?;
+                        write_list(f, values, ", ")?;
+                        f.write_str("]")
+                    }
+                }
+            }
+            Constant::Vector(values) => {
+                f.write_str("<")?;
+                write_list(f, values, ", ")?;
+                f.write_str(">")
+            }
+            Constant::Zero => f.write_str("zeroinitializer"),
+            Constant::Metadata(metadata) => metadata.fmt(f),
+            Constant::Undefined => f.write_str("undef"),
+            Constant::Poison => f.write_str("poison"),
+            Constant::Expression(instruction) => write_constant_expression(f, instruction),
+        }
+    }
+}
+
+/// writes an instruction as a constant expression, which has all of its operands written with their types inside parentheses
+fn write_constant_expression(f: &mut Formatter<'_>, instruction: &Instruction) -> fmt::Result {
+    let exact = |is_exact: bool| if is_exact { " exact" } else { "" };
+
+    match instruction {
+        Instruction::Add { allowed_wrapping, .. } => write!(f, "add{allowed_wrapping}")?,
+        Instruction::Subtract { allowed_wrapping, .. } => write!(f, "sub{allowed_wrapping}")?,
+        Instruction::Multiply { allowed_wrapping, .. } => write!(f, "mul{allowed_wrapping}")?,
+        Instruction::UnsignedDivide { is_exact, .. } => write!(f, "udiv{}", exact(*is_exact))?,
+        Instruction::SignedDivide { is_exact, .. } => write!(f, "sdiv{}", exact(*is_exact))?,
+        Instruction::UnsignedRemainder { .. } => f.write_str("urem")?,
+        Instruction::SignedRemainder { .. } => f.write_str("srem")?,
+        Instruction::ShiftLeft { allowed_wrapping, .. } => write!(f, "shl{allowed_wrapping}")?,
+        Instruction::LogicalShiftRight { is_exact, .. } => write!(f, "lshr{}", exact(*is_exact))?,
+        Instruction::ArithmeticShiftRight { is_exact, .. } => write!(f, "ashr{}", exact(*is_exact))?,
+        Instruction::And { .. } => f.write_str("and")?,
+        Instruction::Or { disjoint, .. } => f.write_str(if *disjoint { "or disjoint" } else { "or" })?,
+        Instruction::ExclusiveOr { .. } => f.write_str("xor")?,
+        Instruction::ExtractElement { .. } => f.write_str("extractelement")?,
+        Instruction::InsertElement { .. } => f.write_str("insertelement")?,
+        Instruction::ShuffleVector { .. } => f.write_str("shufflevector")?,
+        Instruction::GetElementPointer { kind, pointer_type, pointer, indices } => {
+            f.write_str("getelementptr")?;
+
+            match kind {
+                GetPointerKind::Regular => (),
+                GetPointerKind::InBounds => f.write_str(" inbounds")?,
+                GetPointerKind::InRange(start, end) => write!(f, " inrange({start}, {end})")?,
+            }
+
+            write!(f, " ({pointer_type}, {pointer}")?;
+
+            for index in indices {
+                write!(f, ", {index}")?;
+            }
+
+            return f.write_str(")");
+        }
+        Instruction::Truncate { allowed_wrapping, value, new_type } => return write!(f, "trunc{allowed_wrapping} ({value} to {new_type})"),
+        Instruction::ZeroExtend { value, new_type } => return write!(f, "zext ({value} to {new_type})"),
+        Instruction::SignExtend { value, new_type } => return write!(f, "sext ({value} to {new_type})"),
+        Instruction::FloatTruncate { value, new_type, .. } => return write!(f, "fptrunc ({value} to {new_type})"),
+        Instruction::FloatExtend { value, new_type, .. } => return write!(f, "fpext ({value} to {new_type})"),
+        Instruction::FloatToUnsignedInteger { value, new_type } => return write!(f, "fptoui ({value} to {new_type})"),
+        Instruction::FloatToSignedInteger { value, new_type } => return write!(f, "fptosi ({value} to {new_type})"),
+        Instruction::UnsignedIntegerToFloat { value, new_type } => return write!(f, "uitofp ({value} to {new_type})"),
+        Instruction::SignedIntegerToFloat { value, new_type } => return write!(f, "sitofp ({value} to {new_type})"),
+        Instruction::PointerToInteger { value, new_type } => return write!(f, "ptrtoint ({value} to {new_type})"),
+        Instruction::IntegerToPointer { value, new_type } => return write!(f, "inttoptr ({value} to {new_type})"),
+        Instruction::BitCast { value, new_type } => return write!(f, "bitcast ({value} to {new_type})"),
+        Instruction::AddressSpaceCast { value, new_type } => return write!(f, "addrspacecast ({value} to {new_type})"),
+        Instruction::CompareIntegers { comparison, .. } => write!(f, "icmp {comparison}")?,
+        Instruction::CompareFloats { comparison, .. } => write!(f, "fcmp {comparison}")?,
+        Instruction::Select { .. } => f.write_str("select")?,
+        // nothing else can be a constant expression, so it's written the way it would be in a basic block
+        instruction => return instruction.fmt(f),
+    }
+
+    f.write_str(" (")?;
+    write_list(f, instruction.operands(), ", ")?;
+    f.write_str(")")
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FromConstant {
+                constant_type: Type::Void,
+                constant: Constant::Void,
+            } => f.write_str("void"),
+            Self::FromConstant { constant_type, .. } | Self::FromIdentifier { value_type: constant_type, .. } => write!(f, "{constant_type} {}", Operand(self)),
+            Self::FromInstruction { instruction } => match instruction.get_result_type(&()) {
+                Some(t) => write!(f, "{t} {}", Operand(self)),
+                None => Operand(self).fmt(f),
+            },
+            Self::FromLabel => f.write_str("label"),
+        }
+    }
+}
+
+/// written as a signed decimal number, which is how LLVM writes every integer type but `i1`
+impl Display for Integer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let magnitude = if self.is_negative() {
+            f.write_str("-")?;
+            self.wrapping_neg()
+        } else {
+            self.clone()
+        };
+
+        // digits are divided out 19 at a time, since that's the most that fits in a 64 bit divisor
+        let mut magnitude = magnitude.zero_extend(self.bit_width().max(64));
+        let divisor = Integer::from_u64(magnitude.bit_width(), 10_u64.pow(19));
+        let mut chunks = Vec::new();
+
+        loop {
+            let (quotient, remainder) = magnitude.unsigned_divide(&divisor).unwrap();
+            chunks.push(remainder.to_u64().unwrap());
+            magnitude = quotient;
+
+            if magnitude.is_zero() {
+                break;
+            }
+        }
+
+        write!(f, "{}", chunks.pop().unwrap())?;
+
+        for chunk in chunks.iter().rev() {
+            write!(f, "{chunk:019}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// an operand of a metadata tuple or specialized node, which is written as `null` if it's missing
+struct MetadataOperand<'a>(&'a Option<Metadata>);
+
+impl Display for MetadataOperand<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(metadata) => metadata.fmt(f),
+            None => f.write_str("null"),
+        }
+    }
+}
+
+impl Display for Metadata {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Node(id) => write!(f, "!{id}"),
+            Self::String(s) => {
+                f.write_str("!")?;
+                write_string(f, s)
+            }
+            Self::Value(value) => value.fmt(f),
+            Self::Tuple(operands) => {
+                f.write_str("!{")?;
+                write_list(f, operands.iter().map(MetadataOperand), ", ")?;
+                f.write_str("}")
+            }
+            Self::Specialized(node) => node.fmt(f),
+        }
+    }
+}
+
+impl Display for SpecializedMetadata {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "!{}(", self.kind)?;
+
+        for (index, (name, value)) in self.fields.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+
+            if let Some(name) = name {
+                write!(f, "{name}: ")?;
+            }
+
+            value.fmt(f)?;
+        }
+
+        f.write_str(")")
+    }
+}
+
+impl Display for MetadataField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(value) => value.fmt(f),
+            Self::Boolean(value) => value.fmt(f),
+            Self::String(s) => write_string(f, s),
+            Self::Flags(flags) => write_list(f, flags, " | "),
+            Self::Metadata(metadata) => MetadataOperand(metadata).fmt(f),
+        }
+    }
+}
+
+impl Display for MetadataAttachment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.kind, self.node)
+    }
+}
+
+/// writes the metadata attached to an instruction or global variable, each with a comma before it
+fn write_metadata_attachments(f: &mut Formatter<'_>, metadata: &[MetadataAttachment]) -> fmt::Result {
+    for attachment in metadata {
+        write!(f, ", {attachment}")?;
+    }
+
+    Ok(())
+}
+
+impl Display for MetadataDefinition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "!{} = ", self.id)?;
+
+        if self.is_distinct {
+            f.write_str("distinct ")?;
+        }
+
+        self.node.fmt(f)
+    }
+}
+
+impl Display for NamedMetadata {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = !{{", self.name)?;
+        write_list(f, self.nodes.iter().map(|id| format!("!{id}")), ", ")?;
+        f.write_str("}")
+    }
+}
+
+impl Display for AddressSpace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Numbered(number) => write!(f, "addrspace({number})"),
+            Self::Named(name) => {
+                f.write_str("addrspace(")?;
+                write_string(f, name)?;
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+impl Display for FloatingPointKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Binary16 => "half",
+            Self::Brain => "bfloat",
+            Self::Binary32 => "float",
+            Self::Binary64 => "double",
+            Self::Binary128 => "fp128",
+            Self::X86Fp80 => "x86_fp80",
+            Self::PpcFp128 => "ppc_fp128",
+        })
+    }
+}
+
+impl Display for FloatingPointValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (low, high) = (self.bits as u64, (self.bits >> 64) as u64);
+
+        match self.kind {
+            FloatingPointKind::Binary16 => write!(f, "0xH{:04X}", self.bits),
+            FloatingPointKind::Brain => write!(f, "0xR{:04X}", self.bits),
+            FloatingPointKind::X86Fp80 => write!(f, "0xK{:020X}", self.bits),
+            FloatingPointKind::Binary128 => write!(f, "0xL{low:016X}{high:016X}"),
+            FloatingPointKind::PpcFp128 => write!(f, "0xM{low:016X}{high:016X}"),
+            FloatingPointKind::Binary32 | FloatingPointKind::Binary64 => {
+                let value = self.to_f64().unwrap();
+
+                if !value.is_finite() {
+                    return write!(f, "0x{:016X}", value.to_bits());
+                }
+
+                // rust's formatting always round trips, but leaves the decimal point out of whole numbers
+                let formatted = format!("{value:e}");
+                let (mantissa, exponent) = formatted.split_once('e').unwrap();
+                let point = if mantissa.contains('.') { "" } else { ".0" };
+
+                write!(f, "{mantissa}{point}e{:+03}", exponent.parse::<i32>().unwrap())
+            }
+        }
+    }
+}
+
+impl Display for TargetExtensionParameter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Type(t) => t.fmt(f),
+            Self::Integer(value) => value.fmt(f),
+        }
+    }
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Void => f.write_str("void"),
+            Self::Function {
+                return_type,
+                parameters,
+                has_varargs,
+            } => {
+                write!(f, "{return_type} (")?;
+                write_list(f, parameters, ", ")?;
+
+                match (*has_varargs, parameters.is_empty()) {
+                    (true, true) => f.write_str("...)"),
+                    (true, false) => f.write_str(", ...)"),
+                    (false, _) => f.write_str(")"),
+                }
+            }
+            Self::Integer { bit_width } => write!(f, "i{bit_width}"),
+            Self::FloatingPoint { kind } => kind.fmt(f),
+            Self::AMX => f.write_str("x86_amx"),
+            Self::MMX => f.write_str("x86_mmx"),
+            Self::Pointer {
+                address_space: AddressSpace::Numbered(0),
+            } => f.write_str("ptr"),
+            Self::Pointer { address_space } => write!(f, "ptr {address_space}"),
+            Self::TargetExtension { name, parameters } => {
+                f.write_str("target(")?;
+                write_string(f, name)?;
+
+                for parameter in parameters {
+                    write!(f, ", {parameter}")?;
+                }
+
+                f.write_str(")")
+            }
+            Self::Vector {
+                length,
+                element_type,
+                is_scalable: false,
+            } => write!(f, "<{length} x {element_type}>"),
+            Self::Vector {
+                length,
+                element_type,
+                is_scalable: true,
+            } => write!(f, "<vscale x {length} x {element_type}>"),
+            Self::Label => f.write_str("label"),
+            Self::Token => f.write_str("token"),
+            Self::Metadata => f.write_str("metadata"),
+            Self::Array { length, element_type } => write!(f, "[{length} x {element_type}]"),
+            Self::Structure { types, is_packed } => {
+                f.write_str(if *is_packed { "<{ " } else { "{ " })?;
+                write_list(f, types, ", ")?;
+                f.write_str(if *is_packed { " }>" } else { " }" })
+            }
+            Self::OpaqueStructure => f.write_str("opaque"),
+            Self::Identified { name } => Identifier(name).fmt(f),
+        }
+    }
+}
+
+impl Display for ParameterAttribute {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroExtend => f.write_str("zeroext"),
+            Self::SignExtend => f.write_str("signext"),
+            Self::TargetDependent => f.write_str("inreg"),
+            Self::PassByValue(t) => write!(f, "byval({t})"),
+            Self::PassByReference(t) => write!(f, "byref({t})"),
+            Self::PreAllocated(t) => write!(f, "preallocated({t})"),
+            Self::StackAllocated(t) => write!(f, "inalloca({t})"),
+            Self::ReturnStructure(t) => write!(f, "sret({t})"),
+            Self::ElementType(t) => write!(f, "elementtype({t})"),
+            Self::Alignment(alignment) => write!(f, "align {alignment}"),
+            Self::NoAlias => f.write_str("noalias"),
+            Self::NoCapture => f.write_str("nocapture"),
+            Self::NoFree => f.write_str("nofree"),
+            Self::Nest => f.write_str("nest"),
+            Self::Returned => f.write_str("returned"),
+            Self::NonNull => f.write_str("nonnull"),
+            Self::Dereferenceable(bytes) => write!(f, "dereferenceable({bytes})"),
+            Self::DereferenceableOrNull(bytes) => write!(f, "dereferenceable_or_null({bytes})"),
+            Self::Context => f.write_str("swiftself"),
+            Self::SwiftAsync => f.write_str("swiftasync"),
+            Self::SwiftError => f.write_str("swifterror"),
+            Self::Immediate => f.write_str("immarg"),
+            Self::NoUndefined => f.write_str("noundef"),
+            Self::NoFloatingPointClass(classes) => write!(f, "nofpclass({classes})"),
+            Self::StackAlignment(alignment) => write!(f, "alignstack({alignment})"),
+            Self::AllocationAlignment => f.write_str("allocalign"),
+            Self::AllocatedPointer => f.write_str("allocptr"),
+            Self::NoDereference => f.write_str("readnone"),
+            Self::ReadOnly => f.write_str("readonly"),
+            Self::WriteOnly => f.write_str("writeonly"),
+            Self::Writable => f.write_str("writable"),
+            Self::Initializes(ranges) => {
+                f.write_str("initializes(")?;
+                write_list(f, ranges.iter().map(|(start, end)| format!("({start}, {end})")), ", ")?;
+                f.write_str(")")
+            }
+            // like in LLVM, the components for returning are only written when they're different
+            Self::Captures { other, returned } if other == returned => write!(f, "captures({other})"),
+            Self::Captures { other, returned } if *other == CaptureComponents::NONE => write!(f, "captures(ret: {returned})"),
+            Self::Captures { other, returned } => write!(f, "captures({other}, ret: {returned})"),
+            Self::PoisonOnUnwind => f.write_str("dead_on_unwind"),
+            Self::Range {
+                range_type,
+                low_inclusive,
+                high_exclusive,
+            } => write!(f, "range({range_type} {low_inclusive}, {high_exclusive})"),
+        }
+    }
+}
+
+/// writes each attribute followed by a space
+fn write_attributes(f: &mut Formatter<'_>, attributes: &[ParameterAttribute]) -> fmt::Result {
+    for attribute in attributes {
+        write!(f, "{attribute} ")?;
+    }
+
+    Ok(())
+}
+
+/// the classes are written with the shortest names that cover them, like LLVM does
+impl Display for FloatingPointClasses {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut remaining = *self;
+        let mut names = Vec::new();
+
+        for (classes, name) in [
+            (Self::ALL, "all"),
+            (Self::NAN, "nan"),
+            (Self::SIGNALING_NAN, "snan"),
+            (Self::QUIET_NAN, "qnan"),
+            (Self::INFINITY, "inf"),
+            (Self::NEGATIVE_INFINITY, "ninf"),
+            (Self::POSITIVE_INFINITY, "pinf"),
+            (Self::ZERO, "zero"),
+            (Self::NEGATIVE_ZERO, "nzero"),
+            (Self::POSITIVE_ZERO, "pzero"),
+            (Self::SUBNORMAL, "sub"),
+            (Self::NEGATIVE_SUBNORMAL, "nsub"),
+            (Self::POSITIVE_SUBNORMAL, "psub"),
+            (Self::NORMAL, "norm"),
+            (Self::NEGATIVE_NORMAL, "nnorm"),
+            (Self::POSITIVE_NORMAL, "pnorm"),
+        ] {
+            if remaining.contains(classes) {
+                names.push(name);
+                remaining = Self(remaining.0 & !classes.0);
+            }
+        }
+
+        // an empty set has no names, so it's written as a number
+        if names.is_empty() {
+            return write!(f, "{}", self.0);
+        }
+
+        write_list(f, names, " ")
+    }
+}
+
+impl Display for CaptureComponents {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if *self == Self::NONE {
+            return f.write_str("none");
+        }
+
+        if *self == Self::FULL {
+            return f.write_str("full");
+        }
+
+        let address = [(Self::ADDRESS, "address"), (Self::ADDRESS_IS_NULL, "address_is_null")].into_iter().find(|(components, _)| self.contains(*components));
+        let provenance = [(Self::PROVENANCE, "provenance"), (Self::READ_PROVENANCE, "read_provenance")].into_iter().find(|(components, _)| self.contains(*components));
+        write_list(f, address.into_iter().chain(provenance).map(|(_, name)| name), ", ")
+    }
+}
+
+/// a call argument is written with its parameter attributes between its type and its value
+impl Display for CallArgument {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.value.get_type(&()) {
+            Some(t) if !self.attributes.is_empty() => {
+                write!(f, "{t} ")?;
+                write_attributes(f, &self.attributes)?;
+                Operand(&self.value).fmt(f)
+            }
+            _ => self.value.fmt(f),
+        }
+    }
+}
+
+impl Display for FunctionAttribute {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AllocationKind(kind) => {
+                f.write_str("allockind(")?;
+                write_string(f, kind)?;
+                f.write_str(")")
+            }
+            Self::AllocationSize { element_size, count: None } => write!(f, "allocsize({element_size})"),
+            Self::AllocationSize {
+                element_size,
+                count: Some(count),
+            } => write!(f, "allocsize({element_size}, {count})"),
+            Self::AlwaysInline => f.write_str("alwaysinline"),
+            Self::Builtin => f.write_str("builtin"),
+            Self::Cold => f.write_str("cold"),
+            Self::Convergent => f.write_str("convergent"),
+            Self::DisableSanitizerInstrumentation => f.write_str("disable_sanitizer_instrumentation"),
+            Self::Hot => f.write_str("hot"),
+            Self::InlineHint => f.write_str("inlinehint"),
+            Self::JumpTable => f.write_str("jumptable"),
+            Self::Memory { default, locations } => {
+                f.write_str("memory(")?;
+
+                if let Some(access) = default {
+                    write!(f, "{access}")?;
+                }
+
+                for (index, (location, access)) in locations.iter().enumerate() {
+                    if index > 0 || default.is_some() {
+                        f.write_str(", ")?;
+                    }
+
+                    write!(f, "{location}: {access}")?;
+                }
+
+                f.write_str(")")
+            }
+            Self::MinimumSize => f.write_str("minsize"),
+            Self::MustProgress => f.write_str("mustprogress"),
+            Self::Naked => f.write_str("naked"),
+            Self::NoBuiltin => f.write_str("nobuiltin"),
+            Self::NoCallback => f.write_str("nocallback"),
+            Self::NoControlFlowCheck => f.write_str("nocf_check"),
+            Self::NoDuplicate => f.write_str("noduplicate"),
+            Self::NoFree => f.write_str("nofree"),
+            Self::NoImplicitFloat => f.write_str("noimplicitfloat"),
+            Self::NoInline => f.write_str("noinline"),
+            Self::NoMerge => f.write_str("nomerge"),
+            Self::NonLazyBind => f.write_str("nonlazybind"),
+            Self::NoProfile => f.write_str("noprofile"),
+            Self::NoRecurse => f.write_str("norecurse"),
+            Self::NoRedZone => f.write_str("noredzone"),
+            Self::NoReturn => f.write_str("noreturn"),
+            Self::NoSynchronize => f.write_str("nosync"),
+            Self::NoUnwind => f.write_str("nounwind"),
+            Self::NullPointerIsValid => f.write_str("null_pointer_is_valid"),
+            Self::OptimizeNone => f.write_str("optnone"),
+            Self::OptimizeForSize => f.write_str("optsize"),
+            Self::PreSplitCoroutine => f.write_str("presplitcoroutine"),
+            Self::ReturnsTwice => f.write_str("returns_twice"),
+            Self::SafeStack => f.write_str("safestack"),
+            Self::SanitizeAddress => f.write_str("sanitize_address"),
+            Self::SanitizeHardwareAddress => f.write_str("sanitize_hwaddress"),
+            Self::SanitizeMemory => f.write_str("sanitize_memory"),
+            Self::SanitizeThread => f.write_str("sanitize_thread"),
+            Self::ShadowCallStack => f.write_str("shadowcallstack"),
+            Self::Speculatable => f.write_str("speculatable"),
+            Self::SpeculativeLoadHardening => f.write_str("speculative_load_hardening"),
+            Self::StackAlignment(alignment) => write!(f, "alignstack({alignment})"),
+            Self::StackProtect => f.write_str("ssp"),
+            Self::StackProtectRequired => f.write_str("sspreq"),
+            Self::StackProtectStrong => f.write_str("sspstrong"),
+            Self::StrictFloatingPoint => f.write_str("strictfp"),
+            Self::UnwindTable { is_synchronous: false } => f.write_str("uwtable"),
+            Self::UnwindTable { is_synchronous: true } => f.write_str("uwtable(sync)"),
+            Self::VScaleRange { minimum, maximum: None } => write!(f, "vscale_range({minimum})"),
+            Self::VScaleRange {
+                minimum,
+                maximum: Some(maximum),
+            } => write!(f, "vscale_range({minimum},{maximum})"),
+            Self::WillReturn => f.write_str("willreturn"),
+            Self::String { key, value } => {
+                write_string(f, key)?;
+
+                match value {
+                    Some(value) => {
+                        f.write_str("=")?;
+                        write_string(f, value)
+                    }
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl Display for MemoryLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Argument => f.write_str("argmem"),
+            Self::Inaccessible => f.write_str("inaccessiblemem"),
+            Self::ErrorNumber => f.write_str("errnomem"),
+        }
+    }
+}
+
+impl Display for MemoryAccess {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => f.write_str("none"),
+            Self::Read => f.write_str("read"),
+            Self::Write => f.write_str("write"),
+            Self::ReadWrite => f.write_str("readwrite"),
+        }
+    }
+}
+
+/// writes the function attributes of a function or call followed by the attribute groups it references, with a space before each one
+fn write_function_attributes(f: &mut Formatter<'_>, attributes: &[FunctionAttribute], attribute_groups: &[usize]) -> fmt::Result {
+    for attribute in attributes {
+        write!(f, " {attribute}")?;
+    }
+
+    for group in attribute_groups {
+        write!(f, " #{group}")?;
+    }
+
+    Ok(())
+}
+
+/// `alignstack` is written as `alignstack=16` in attribute groups, unlike everywhere else
+impl Display for AttributeGroup {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "attributes #{} = {{", self.id)?;
+
+        for attribute in &self.attributes {
+            match attribute {
+                FunctionAttribute::StackAlignment(alignment) => write!(f, " alignstack={alignment}")?,
+                attribute => write!(f, " {attribute}")?,
+            }
+        }
+
+        f.write_str(" }")
+    }
+}
+
+impl Display for AllowedWrapping {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (self.can_wrap_unsigned, self.can_wrap_signed) {
+            (true, true) => Ok(()),
+            (false, true) => f.write_str(" nuw"),
+            (true, false) => f.write_str(" nsw"),
+            (false, false) => f.write_str(" nuw nsw"),
+        }
+    }
+}
+
+/// written with a space before each flag, so nothing is written if no flags are set
+impl Display for FastMathFlags {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if *self == Self::FAST {
+            return f.write_str(" fast");
+        }
+
+        for (is_set, name) in [
+            (self.no_nans, " nnan"),
+            (self.no_infinities, " ninf"),
+            (self.no_signed_zeros, " nsz"),
+            (self.allow_reciprocal, " arcp"),
+            (self.allow_contraction, " contract"),
+            (self.approximate_functions, " afn"),
+            (self.allow_reassociation, " reassoc"),
+        ] {
+            if is_set {
+                f.write_str(name)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for AtomicOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Exchange => "xchg",
+            Self::Add => "add",
+            Self::Subtract => "sub",
+            Self::And => "and",
+            Self::Nand => "nand",
+            Self::Or => "or",
+            Self::ExclusiveOr => "xor",
+            Self::SignedMax => "max",
+            Self::SignedMin => "min",
+            Self::UnsignedMax => "umax",
+            Self::UnsignedMin => "umin",
+            Self::FloatAdd => "fadd",
+            Self::FloatSubtract => "fsub",
+            Self::FloatMax => "fmax",
+            Self::FloatMin => "fmin",
+            Self::UnsignedIncrementWrap => "uinc_wrap",
+            Self::UnsignedDecrementWrap => "udec_wrap",
+        })
+    }
+}
+
+impl Display for Ordering {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Unordered => "unordered",
+            Self::Monotonic => "monotonic",
+            Self::Acquire => "acquire",
+            Self::Release => "release",
+            Self::AcquireRelease => "acq_rel",
+            Self::SequentiallyConsistent => "seq_cst",
+        })
+    }
+}
+
+impl Display for IntegerComparison {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Equal => "eq",
+            Self::NotEqual => "ne",
+            Self::UnsignedGreaterThan => "ugt",
+            Self::UnsignedGreaterOrEqual => "uge",
+            Self::UnsignedLessThan => "ult",
+            Self::UnsignedLessOrEqual => "ule",
+            Self::SignedGreaterThan => "sgt",
+            Self::SignedGreaterOrEqual => "sge",
+            Self::SignedLessThan => "slt",
+            Self::SignedLessOrEqual => "sle",
+        })
+    }
+}
+
+impl Display for FloatComparison {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::False => "false",
+            Self::OrderedEqual => "oeq",
+            Self::OrderedGreaterThan => "ogt",
+            Self::OrderedGreaterOrEqual => "oge",
+            Self::OrderedLessThan => "olt",
+            Self::OrderedLessOrEqual => "ole",
+            Self::OrderedNotEqual => "one",
+            Self::Ordered => "ord",
+            Self::UnorderedEqual => "ueq",
+            Self::UnorderedGreaterThan => "ugt",
+            Self::UnorderedGreaterOrEqual => "uge",
+            Self::UnorderedLessThan => "ult",
+            Self::UnorderedLessOrEqual => "ule",
+            Self::UnorderedNotEqual => "une",
+            Self::Unordered => "uno",
+            Self::True => "true",
+        })
+    }
+}
+
+/// writes `syncscope("...") ` if there's a sync scope
+fn write_sync_scope(f: &mut Formatter<'_>, sync_scope: &Option<String>) -> fmt::Result {
+    if let Some(scope) = sync_scope {
+        f.write_str("syncscope(")?;
+        write_string(f, scope)?;
+        f.write_str(") ")?;
+    }
+
+    Ok(())
+}
+
+/// writes `, align N` if there's an alignment
+fn write_alignment(f: &mut Formatter<'_>, alignment: Option<usize>) -> fmt::Result {
+    match alignment {
+        Some(alignment) => write!(f, ", align {alignment}"),
+        None => Ok(()),
+    }
+}
+
+/// writes the calling convention, return value attributes, callee and arguments of a call, which are shared by `call` and `invoke`
+fn write_call(
+    f: &mut Formatter<'_>,
+    calling_convention: &Option<String>,
+    return_value_attributes: &[ParameterAttribute],
+    address_space: &Option<AddressSpace>,
+    function_type: &Type,
+    callee: &Value,
+    function_arguments: &[CallArgument],
+) -> fmt::Result {
+    if let Some(calling_convention) = calling_convention {
+        write!(f, "{calling_convention} ")?;
+    }
+
+    write_attributes(f, return_value_attributes)?;
+
+    if let Some(address_space) = address_space {
+        write!(f, "{address_space} ")?;
+    }
+
+    // like in LLVM, the whole function type is only written when the function has varargs
+    match function_type {
+        Type::Function {
+            return_type, has_varargs: false, ..
+        } => write!(f, "{return_type} ")?,
+        _ => write!(f, "{function_type} ")?,
+    }
+
+    write!(f, "{}(", Operand(callee))?;
+    write_list(f, function_arguments, ", ")?;
+    f.write_str(")")
+}
+
+impl Display for OperandBundle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write_string(f, &self.tag)?;
+        f.write_str("(")?;
+        write_list(f, &self.inputs, ", ")?;
+        f.write_str(")")
+    }
+}
+
+/// writes the operand bundles of a call with a space before them, if there are any
+fn write_operand_bundles(f: &mut Formatter<'_>, operand_bundles: &[OperandBundle]) -> fmt::Result {
+    if operand_bundles.is_empty() {
+        return Ok(());
+    }
+
+    f.write_str(" [ ")?;
+    write_list(f, operand_bundles, ", ")?;
+    f.write_str(" ]")
+}
+
+/// writes everything about a call to inline assembly after the instruction name, which is shared by `call` and `callbr`
+fn write_assembly_call(
+    f: &mut Formatter<'_>,
+    return_value_attributes: &[ParameterAttribute],
+    call_type: &Type,
+    hints: &AssemblyCallHints,
+    template: &str,
+    operand_constraints: &str,
+    arguments: &[CallArgument],
+) -> fmt::Result {
+    write_attributes(f, return_value_attributes)?;
+    write!(f, "{call_type} asm ")?;
+
+    for (hint, name) in [
+        (hints.has_other_side_effects, "sideeffect "),
+        (hints.should_align_stack, "alignstack "),
+        (hints.is_intel_dialect, "inteldialect "),
+        (hints.can_unwind, "unwind "),
+    ] {
+        if hint {
+            f.write_str(name)?;
+        }
+    }
+
+    write_string(f, template)?;
+    f.write_str(", ")?;
+    write_string(f, operand_constraints)?;
+    f.write_str("(")?;
+    write_list(f, arguments, ", ")?;
+    f.write_str(")")
+}
+
+/// writes where a funclet pad unwinds to
+fn write_unwind_destination(f: &mut Formatter<'_>, unwind_destination: &Option<Arc<Value>>) -> fmt::Result {
+    match unwind_destination {
+        Some(destination) => write!(f, "unwind {destination}"),
+        None => f.write_str("unwind to caller"),
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let binary = |f: &mut Formatter<'_>, name: &str, flags: &dyn Display, left_hand_side: &Value, right_hand_side: &Value| write!(f, "{name}{flags} {left_hand_side}, {}", Operand(right_hand_side));
+        let exact = |is_exact: bool| if is_exact { " exact" } else { "" };
+        let volatile = |is_volatile: bool| if is_volatile { "volatile " } else { "" };
+
+        match self {
+            Self::FloatNegate { fast_math_flags, value } => write!(f, "fneg{fast_math_flags} {value}"),
+            Self::Add {
+                left_hand_side,
+                right_hand_side,
+                allowed_wrapping,
+            } => binary(f, "add", allowed_wrapping, left_hand_side, right_hand_side),
+            Self::FloatAdd {
+                left_hand_side,
+                right_hand_side,
+                fast_math_flags,
+            } => binary(f, "fadd", fast_math_flags, left_hand_side, right_hand_side),
+            Self::FloatSubtract {
+                left_hand_side,
+                right_hand_side,
+                fast_math_flags,
+            } => binary(f, "fsub", fast_math_flags, left_hand_side, right_hand_side),
+            Self::FloatMultiply {
+                left_hand_side,
+                right_hand_side,
+                fast_math_flags,
+            } => binary(f, "fmul", fast_math_flags, left_hand_side, right_hand_side),
+            Self::FloatDivide {
+                left_hand_side,
+                right_hand_side,
+                fast_math_flags,
+            } => binary(f, "fdiv", fast_math_flags, left_hand_side, right_hand_side),
+            Self::FloatRemainder {
+                left_hand_side,
+                right_hand_side,
+                fast_math_flags,
+            } => binary(f, "frem", fast_math_flags, left_hand_side, right_hand_side),
+            Self::Subtract {
+                left_hand_side,
+                right_hand_side,
+                allowed_wrapping,
+            } => binary(f, "sub", allowed_wrapping, left_hand_side, right_hand_side),
+            Self::Multiply {
+                left_hand_side,
+                right_hand_side,
+                allowed_wrapping,
+            } => binary(f, "mul", allowed_wrapping, left_hand_side, right_hand_side),
+            Self::UnsignedDivide {
+                left_hand_side,
+                right_hand_side,
+                is_exact,
+            } => binary(f, "udiv", &exact(*is_exact), left_hand_side, right_hand_side),
+            Self::SignedDivide {
+                left_hand_side,
+                right_hand_side,
+                is_exact,
+            } => binary(f, "sdiv", &exact(*is_exact), left_hand_side, right_hand_side),
+            Self::UnsignedRemainder { left_hand_side, right_hand_side } => binary(f, "urem", &"", left_hand_side, right_hand_side),
+            Self::SignedRemainder { left_hand_side, right_hand_side } => binary(f, "srem", &"", left_hand_side, right_hand_side),
+            Self::ShiftLeft {
+                left_hand_side,
+                right_hand_side,
+                allowed_wrapping,
+            } => binary(f, "shl", allowed_wrapping, left_hand_side, right_hand_side),
+            Self::LogicalShiftRight {
+                left_hand_side,
+                right_hand_side,
+                is_exact,
+            } => binary(f, "lshr", &exact(*is_exact), left_hand_side, right_hand_side),
+            Self::ArithmeticShiftRight {
+                left_hand_side,
+                right_hand_side,
+                is_exact,
+            } => binary(f, "ashr", &exact(*is_exact), left_hand_side, right_hand_side),
+            Self::And { left_hand_side, right_hand_side } => binary(f, "and", &"", left_hand_side, right_hand_side),
+            Self::Or {
+                left_hand_side,
+                right_hand_side,
+                disjoint,
+            } => binary(f, "or", &if *disjoint { " disjoint" } else { "" }, left_hand_side, right_hand_side),
+            Self::ExclusiveOr { left_hand_side, right_hand_side } => binary(f, "xor", &"", left_hand_side, right_hand_side),
+            Self::ExtractElement { vector, index } => write!(f, "extractelement {vector}, {index}"),
+            Self::InsertElement { vector, value, index } => write!(f, "insertelement {vector}, {value}, {index}"),
+            Self::ShuffleVector { first, second, mask } => write!(f, "shufflevector {first}, {second}, {mask}"),
+            Self::ExtractValue { aggregate, indices } => {
+                write!(f, "extractvalue {aggregate}, ")?;
+                write_list(f, indices, ", ")
+            }
+            Self::InsertValue { aggregate, value, indices } => {
+                write!(f, "insertvalue {aggregate}, {value}, ")?;
+                write_list(f, indices, ", ")
+            }
+            Self::StackAllocate {
+                can_reuse,
+                value_type,
+                num_elements,
+                alignment,
+                address_space,
+            } => {
+                write!(f, "alloca {}{value_type}", if *can_reuse { "inalloca " } else { "" })?;
+
+                if let Some(num_elements) = num_elements {
+                    write!(f, ", {num_elements}")?;
+                }
+
+                write_alignment(f, *alignment)?;
+
+                match address_space {
+                    Some(address_space) => write!(f, ", {address_space}"),
+                    None => Ok(()),
+                }
+            }
+            Self::Load {
+                is_volatile,
+                result_type,
+                pointer,
+                alignment,
+            } => {
+                write!(f, "load {}{result_type}, {pointer}", volatile(*is_volatile))?;
+                write_alignment(f, *alignment)
+            }
+            Self::AtomicLoad {
+                is_volatile,
+                result_type,
+                pointer,
+                ordering,
+                sync_scope,
+                alignment,
+            } => {
+                write!(f, "load atomic {}{result_type}, {pointer} ", volatile(*is_volatile))?;
+                write_sync_scope(f, sync_scope)?;
+                write!(f, "{ordering}, align {alignment}")
+            }
+            Self::Store {
+                is_volatile,
+                value,
+                pointer,
+                alignment,
+            } => {
+                write!(f, "store {}{value}, {pointer}", volatile(*is_volatile))?;
+                write_alignment(f, *alignment)
+            }
+            Self::AtomicStore {
+                is_volatile,
+                value,
+                pointer,
+                ordering,
+                sync_scope,
+                alignment,
+            } => {
+                write!(f, "store atomic {}{value}, {pointer} ", volatile(*is_volatile))?;
+                write_sync_scope(f, sync_scope)?;
+                write!(f, "{ordering}, align {alignment}")
+            }
+            Self::Fence { ordering, sync_scope } => {
+                f.write_str("fence ")?;
+                write_sync_scope(f, sync_scope)?;
+                ordering.fmt(f)
+            }
+            Self::CompareExchange {
+                is_weak,
+                is_volatile,
+                pointer,
+                compare_value,
+                new_value,
+                sync_scope,
+                success_ordering,
+                failure_ordering,
+                alignment,
+            } => {
+                let weak = if *is_weak { "weak " } else { "" };
+                write!(f, "cmpxchg {weak}{}{pointer}, {compare_value}, {new_value} ", volatile(*is_volatile))?;
+                write_sync_scope(f, sync_scope)?;
+                write!(f, "{success_ordering} {failure_ordering}")?;
+                write_alignment(f, *alignment)
+            }
+            Self::AtomicReadModifyWrite {
+                is_volatile,
+                operation,
+                pointer,
+                value,
+                sync_scope,
+                ordering,
+                alignment,
+            } => {
+                write!(f, "atomicrmw {}{operation} {pointer}, {value} ", volatile(*is_volatile))?;
+                write_sync_scope(f, sync_scope)?;
+                ordering.fmt(f)?;
+                write_alignment(f, *alignment)
+            }
+            Self::GetElementPointer { kind, pointer_type, pointer, indices } => {
+                f.write_str("getelementptr ")?;
+
+                match kind {
+                    GetPointerKind::Regular => (),
+                    GetPointerKind::InBounds => f.write_str("inbounds ")?,
+                    GetPointerKind::InRange(start, end) => write!(f, "inrange({start}, {end}) ")?,
+                }
+
+                write!(f, "{pointer_type}, {pointer}")?;
+
+                for index in indices {
+                    write!(f, ", {index}")?;
+                }
+
+                Ok(())
+            }
+            Self::Truncate { allowed_wrapping, value, new_type } => write!(f, "trunc{allowed_wrapping} {value} to {new_type}"),
+            Self::ZeroExtend { value, new_type } => write!(f, "zext {value} to {new_type}"),
+            Self::SignExtend { value, new_type } => write!(f, "sext {value} to {new_type}"),
+            Self::FloatTruncate { fast_math_flags, value, new_type } => write!(f, "fptrunc{fast_math_flags} {value} to {new_type}"),
+            Self::FloatExtend { fast_math_flags, value, new_type } => write!(f, "fpext{fast_math_flags} {value} to {new_type}"),
+            Self::FloatToUnsignedInteger { value, new_type } => write!(f, "fptoui {value} to {new_type}"),
+            Self::FloatToSignedInteger { value, new_type } => write!(f, "fptosi {value} to {new_type}"),
+            Self::UnsignedIntegerToFloat { value, new_type } => write!(f, "uitofp {value} to {new_type}"),
+            Self::SignedIntegerToFloat { value, new_type } => write!(f, "sitofp {value} to {new_type}"),
+            Self::PointerToInteger { value, new_type } => write!(f, "ptrtoint {value} to {new_type}"),
+            Self::IntegerToPointer { value, new_type } => write!(f, "inttoptr {value} to {new_type}"),
+            Self::BitCast { value, new_type } => write!(f, "bitcast {value} to {new_type}"),
+            Self::AddressSpaceCast { value, new_type } => write!(f, "addrspacecast {value} to {new_type}"),
+            Self::CompareIntegers {
+                comparison,
+                left_hand_side,
+                right_hand_side,
+            } => write!(f, "icmp {comparison} {left_hand_side}, {}", Operand(right_hand_side)),
+            Self::CompareFloats {
+                fast_math_flags,
+                comparison,
+                left_hand_side,
+                right_hand_side,
+            } => write!(f, "fcmp{fast_math_flags} {comparison} {left_hand_side}, {}", Operand(right_hand_side)),
+            Self::Select {
+                fast_math_flags,
+                condition,
+                true_value,
+                false_value,
+            } => write!(f, "select{fast_math_flags} {condition}, {true_value}, {false_value}"),
+            Self::Phi {
+                fast_math_flags,
+                value_type,
+                incoming,
+            } => {
+                write!(f, "phi{fast_math_flags} {value_type} ")?;
+
+                for (index, incoming) in incoming.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+
+                    write!(f, "[ {}, {} ]", Operand(&incoming.value), Operand(&incoming.block))?;
+                }
+
+                Ok(())
+            }
+            Self::Freeze { value } => write!(f, "freeze {value}"),
+            Self::Call {
+                tail_call_hint,
+                fast_math_flags,
+                calling_convention,
+                return_value_attributes,
+                address_space,
+                function_type,
+                callee,
+                function_arguments,
+                function_attributes,
+                attribute_groups,
+                operand_bundles,
+            } => {
+                match tail_call_hint {
+                    TailCallHint::Indifferent => (),
+                    TailCallHint::ShouldTail => f.write_str("tail ")?,
+                    TailCallHint::MustTail => f.write_str("musttail ")?,
+                    TailCallHint::NeverTail => f.write_str("notail ")?,
+                }
+
+                write!(f, "call{fast_math_flags} ")?;
+                write_call(f, calling_convention, return_value_attributes, address_space, function_type, callee, function_arguments)?;
+                write_function_attributes(f, function_attributes, attribute_groups)?;
+                write_operand_bundles(f, operand_bundles)
+            }
+            Self::CallAssembly {
+                return_value_attributes,
+                call_type,
+                hints,
+                template,
+                operand_constraints,
+                arguments,
+                function_attributes,
+                attribute_groups,
+            } => {
+                f.write_str("call ")?;
+                write_assembly_call(f, return_value_attributes, call_type, hints, template, operand_constraints, arguments)?;
+                write_function_attributes(f, function_attributes, attribute_groups)
+            }
+            Self::LandingPad { result_type, is_cleanup, clauses } => {
+                write!(f, "landingpad {result_type}")?;
+
+                if *is_cleanup {
+                    f.write_str(" cleanup")?;
+                }
+
+                for clause in clauses {
+                    match clause {
+                        LandingPadClause::Catch(value) => write!(f, " catch {value}")?,
+                        LandingPadClause::Filter(value) => write!(f, " filter {value}")?,
+                    }
+                }
+
+                Ok(())
+            }
+            Self::CatchPad { catch_switch, arguments } => {
+                write!(f, "catchpad within {} [", Operand(catch_switch))?;
+                write_list(f, arguments, ", ")?;
+                f.write_str("]")
+            }
+            Self::CleanupPad { parent, arguments } => {
+                write!(f, "cleanuppad within {} [", Operand(parent))?;
+                write_list(f, arguments, ", ")?;
+                f.write_str("]")
+            }
+        }
+    }
+}
+
+impl Display for Terminator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Return { value } => write!(f, "ret {value}"),
+            Self::ConditionalBranch { condition, if_true, if_false } => write!(f, "br {condition}, {if_true}, {if_false}"),
+            Self::Branch { destination } => write!(f, "br {destination}"),
+            Self::Switch {
+                value,
+                default_destination,
+                destinations,
+            } => {
+                write!(f, "switch {value}, {default_destination} [")?;
+
+                for destination in destinations {
+                    write!(f, " {}, {}", destination.value, destination.destination)?;
+                }
+
+                f.write_str(" ]")
+            }
+            Self::IndirectBranch { address, valid_destinations } => {
+                write!(f, "indirectbr {address}, [ ")?;
+                write_list(f, valid_destinations, ", ")?;
+                f.write_str(" ]")
+            }
+            Self::Invoke {
+                calling_convention,
+                return_value_attributes,
+                address_space,
+                function_type,
+                callee,
+                function_arguments,
+                function_attributes,
+                attribute_groups,
+                operand_bundles,
+                normal_destination,
+                unwind_destination,
+            } => {
+                f.write_str("invoke ")?;
+                write_call(f, calling_convention, return_value_attributes, address_space, function_type, callee, function_arguments)?;
+                write_function_attributes(f, function_attributes, attribute_groups)?;
+                write_operand_bundles(f, operand_bundles)?;
+                write!(f, " to {normal_destination} unwind {unwind_destination}")
+            }
+            Self::CallBranch {
+                return_value_attributes,
+                call_type,
+                hints,
+                template,
+                operand_constraints,
+                arguments,
+                function_attributes,
+                attribute_groups,
+                fallthrough_destination,
+                indirect_destinations,
+            } => {
+                f.write_str("callbr ")?;
+                write_assembly_call(f, return_value_attributes, call_type, hints, template, operand_constraints, arguments)?;
+                write_function_attributes(f, function_attributes, attribute_groups)?;
+                write!(f, " to {fallthrough_destination} [")?;
+                write_list(f, indirect_destinations, ", ")?;
+                f.write_str("]")
+            }
+            Self::Resume { value } => write!(f, "resume {value}"),
+            Self::CatchSwitch {
+                parent,
+                handlers,
+                unwind_destination,
+            } => {
+                write!(f, "catchswitch within {} [", Operand(parent))?;
+                write_list(f, handlers, ", ")?;
+                f.write_str("] ")?;
+                write_unwind_destination(f, unwind_destination)
+            }
+            Self::CatchReturn { catch_pad, destination } => write!(f, "catchret from {} to {destination}", Operand(catch_pad)),
+            Self::CleanupReturn { cleanup_pad, unwind_destination } => {
+                write!(f, "cleanupret from {} ", Operand(cleanup_pad))?;
+                write_unwind_destination(f, unwind_destination)
+            }
+            Self::Unreachable => f.write_str("unreachable"),
+        }
+    }
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let metadata = match self {
+            Self::Assignment { identifier, value, metadata } => {
+                write!(f, "{} = {value}", Identifier(identifier))?;
+                metadata
+            }
+            Self::NoAssignment { instruction, metadata } => {
+                instruction.fmt(f)?;
+                metadata
+            }
+        };
+
+        write_metadata_attachments(f, metadata)
+    }
+}
+
+impl Display for BasicBlock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(name) = &self.name {
+            writeln!(f, "{name}:")?;
+        }
+
+        for operation in &self.operations {
+            writeln!(f, "    {operation}")?;
+        }
+
+        f.write_str("    ")?;
+
+        if let Some(identifier) = &self.terminator_identifier {
+            write!(f, "{} = ", Identifier(identifier))?;
+        }
+
+        self.terminator.fmt(f)?;
+        write_metadata_attachments(f, &self.terminator_metadata)
+    }
+}
+
+impl Display for LinkageType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Private => "private",
+            Self::Internal => "internal",
+            Self::AvailableExternally => "available_externally",
+            Self::LinkOnce => "linkonce",
+            Self::Weak => "weak",
+            Self::Common => "common",
+            Self::Appending => "appending",
+            Self::ExternalWeak => "extern_weak",
+            Self::LinkOnceODR => "linkonce_odr",
+            Self::WeakODR => "weak_odr",
+            Self::External => "external",
+        })
+    }
+}
+
+/// writes the specifiers shared by every global value, each followed by a space. the defaults are left out
+fn write_global_value_specifiers(f: &mut Formatter<'_>, linkage: &LinkageType, preemption_specifier: &PreemptionSpecifier, visibility: &Visibility, dll_storage_class: &DllStorageClass) -> fmt::Result {
+    if *linkage != LinkageType::External {
+        write!(f, "{linkage} ")?;
+    }
+
+    if *preemption_specifier == PreemptionSpecifier::Local {
+        f.write_str("dso_local ")?;
+    }
+
+    match visibility {
+        Visibility::Default => (),
+        Visibility::Hidden => f.write_str("hidden ")?,
+        Visibility::Protected => f.write_str("protected ")?,
+    }
+
+    match dll_storage_class {
+        DllStorageClass::Default => Ok(()),
+        DllStorageClass::Import => f.write_str("dllimport "),
+        DllStorageClass::Export => f.write_str("dllexport "),
+    }
+}
+
+impl Display for UnnamedAddress {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Named => "",
+            Self::Global => "unnamed_addr",
+            Self::Local => "local_unnamed_addr",
+        })
+    }
+}
+
+impl Display for FunctionParameter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.parameter_type.fmt(f)?;
+
+        for attribute in &self.attributes {
+            write!(f, " {attribute}")?;
+        }
+
+        match &self.name {
+            Some(name) => write!(f, " {}", Identifier(name)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for FunctionHeader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write_global_value_specifiers(f, &self.linkage, &self.preemption_specifier, &self.visibility, &self.dll_storage_class)?;
+
+        if let Some(calling_convention) = &self.calling_convention {
+            write!(f, "{calling_convention} ")?;
+        }
+
+        write_attributes(f, &self.return_type_parameter_attributes)?;
+        write!(f, "{} {}(", self.return_type, Identifier(&self.name))?;
+        write_list(f, &self.arguments, ", ")?;
+
+        match (self.has_varargs, self.arguments.is_empty()) {
+            (true, true) => f.write_str("...)")?,
+            (true, false) => f.write_str(", ...)")?,
+            (false, _) => f.write_str(")")?,
+        }
+
+        if self.unnamed_address != UnnamedAddress::Named {
+            write!(f, " {}", self.unnamed_address)?;
+        }
+
+        if let Some(address_space) = &self.address_space {
+            write!(f, " {address_space}")?;
+        }
+
+        write_function_attributes(f, &self.attributes, &self.attribute_groups)?;
+
+        if let Some(section) = &self.section_name {
+            f.write_str(" section ")?;
+            write_string(f, section)?;
+        }
+
+        if let Some(partition) = &self.partition_name {
+            f.write_str(" partition ")?;
+            write_string(f, partition)?;
+        }
+
+        if let Some(comdat) = &self.comdat {
+            write!(f, " comdat({comdat})")?;
+        }
+
+        if let Some(alignment) = self.alignment {
+            write!(f, " align {alignment}")?;
+        }
+
+        if let Some(garbage_collector) = &self.garbage_collector {
+            f.write_str(" gc ")?;
+            write_string(f, garbage_collector)?;
+        }
+
+        for (name, value) in [("prefix", &self.prefix), ("prologue", &self.prologue), ("personality", &self.personality)] {
+            if let Some(value) = value {
+                write!(f, " {name} {value}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Function {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "define {}", self.header)?;
+
+        for attachment in &self.header.metadata {
+            write!(f, " {attachment}")?;
+        }
+
+        writeln!(f, " {{")?;
+
+        for (index, block) in self.basic_blocks.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+
+            writeln!(f, "{block}")?;
+        }
+
+        f.write_str("}")
+    }
+}
+
+impl Display for ThreadLocalMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::GeneralDynamic => "thread_local",
+            Self::LocalDynamic => "thread_local(localdynamic)",
+            Self::InitialExec => "thread_local(initialexec)",
+            Self::LocalExec => "thread_local(localexec)",
+        })
+    }
+}
+
+impl Display for GlobalVariable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = ", Identifier(&self.name))?;
+        // globals without initializers have to say that they're external explicitly
+        if self.initializer.is_none() && self.linkage == LinkageType::External {
+            f.write_str("external ")?;
+        }
+
+        write_global_value_specifiers(f, &self.linkage, &self.preemption_specifier, &self.visibility, &self.dll_storage_class)?;
+
+        if let Some(mode) = &self.thread_local {
+            write!(f, "{mode} ")?;
+        }
+
+        if self.unnamed_address != UnnamedAddress::Named {
+            write!(f, "{} ", self.unnamed_address)?;
+        }
+
+        if let Some(address_space) = &self.address_space {
+            write!(f, "{address_space} ")?;
+        }
+
+        if self.is_externally_initialized {
+            f.write_str("externally_initialized ")?;
+        }
+
+        f.write_str(if self.is_constant { "constant " } else { "global " })?;
+
+        match &self.initializer {
+            Some(initializer) => initializer.fmt(f)?,
+            None => self.value_type.fmt(f)?,
+        }
+
+        if let Some(section) = &self.section_name {
+            f.write_str(", section ")?;
+            write_string(f, section)?;
+        }
+
+        if let Some(partition) = &self.partition_name {
+            f.write_str(", partition ")?;
+            write_string(f, partition)?;
+        }
+
+        if let Some(comdat) = &self.comdat {
+            write!(f, ", comdat({comdat})")?;
+        }
+
+        write_alignment(f, self.alignment)?;
+
+        if let Some(model) = &self.code_model {
+            f.write_str(", code_model ")?;
+            write_string(f, model)?;
+        }
+
+        write_metadata_attachments(f, &self.metadata)
+    }
+}
+
+impl Display for Comdat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let kind = match self.selection_kind {
+            ComdatSelectionKind::Any => "any",
+            ComdatSelectionKind::ExactMatch => "exactmatch",
+            ComdatSelectionKind::Largest => "largest",
+            ComdatSelectionKind::NoDeduplicate => "nodeduplicate",
+            ComdatSelectionKind::SameSize => "samesize",
+        };
+
+        write!(f, "{} = comdat {kind}", self.name)
+    }
+}
+
+impl Display for TypeDefinition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = type {}", Identifier(&self.name), self.definition)
+    }
+}
+
+impl Display for Symbol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FunctionDeclaration(header) => {
+                f.write_str("declare ")?;
+
+                for attachment in &header.metadata {
+                    write!(f, "{attachment} ")?;
+                }
+
+                header.fmt(f)
+            }
+            Self::Function(function) => function.fmt(f),
+            Self::GlobalVariable(variable) => variable.fmt(f),
+        }
+    }
+}
+
+impl Display for Module {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut needs_separator = false;
+
+        for (name, value) in [("source_filename", &self.source_filename), ("target datalayout", &self.data_layout), ("target triple", &self.target_triple)] {
+            if let Some(value) = value {
+                write!(f, "{name} = ")?;
+                write_string(f, value)?;
+                writeln!(f)?;
+                needs_separator = true;
+            }
+        }
+
+        // every kind of top-level entity is separated by a blank line, as is every function definition
+        let comdats = self.comdats.iter().map(|comdat| (comdat.to_string(), 0));
+        let type_definitions = self.type_definitions.iter().map(|definition| (definition.to_string(), 1));
+        let symbols = self.symbols.iter().map(|symbol| {
+            let kind = match symbol {
+                Symbol::GlobalVariable(_) => 2,
+                Symbol::FunctionDeclaration(_) => 3,
+                Symbol::Function(_) => 4,
+            };
+
+            (symbol.to_string(), kind)
+        });
+        let attribute_groups = self.attribute_groups.iter().map(|group| (group.to_string(), 5));
+        let named_metadata = self.named_metadata.iter().map(|metadata| (metadata.to_string(), 6));
+        let metadata_definitions = self.metadata_definitions.iter().map(|definition| (definition.to_string(), 7));
+        let mut previous_kind = None;
+
+        for (item, kind) in comdats.chain(type_definitions).chain(symbols).chain(attribute_groups).chain(named_metadata).chain(metadata_definitions) {
+            if needs_separator && (previous_kind != Some(kind) || kind == 4) {
+                writeln!(f)?;
+            }
+
+            writeln!(f, "{item}")?;
+            needs_separator = true;
+            previous_kind = Some(kind);
+        }
+
+        Ok(())
+    }
+}
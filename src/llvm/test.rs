@@ -1,5 +1,13 @@
-use super::grammar::TypeParser;
-use crate::types::*;
+use super::{
+    grammar::{BasicBlockParser, FunctionDeclarationParser, FunctionParser, GlobalVariableParser, ModuleParser, TypeDefinitionParser, TypeParser},
+    DllStorageClass, LinkageType, Operation, PreemptionSpecifier, Symbol, ThreadLocalMode, UnnamedAddress, Visibility,
+};
+use crate::{
+    integer::Integer,
+    ir::{AtomicOperation, Constant, FastMathFlags, FloatComparison, FloatingPointValue, IncomingValue, Instruction, LandingPadClause, Metadata, MetadataField, Ordering, Terminator, Value},
+    types::*,
+};
+use std::sync::Arc;
 
 /// simple test to ensure the examples given in the LLVM documentation are parsed correctly
 #[test]
@@ -7,6 +15,12 @@ fn type_parsing() {
     assert!(TypeParser::new().parse("i1") == Ok(Type::Integer { bit_width: 1 }));
     assert!(TypeParser::new().parse("i32") == Ok(Type::Integer { bit_width: 32 }));
     assert!(TypeParser::new().parse("i1942652") == Ok(Type::Integer { bit_width: 1942652 }));
+    assert!(TypeParser::new().parse("i8388608") == Ok(Type::Integer { bit_width: 8388608 }));
+    // integers with no bits, or too many bits, aren't allowed
+    assert!(TypeParser::new().parse("i0").is_err());
+    assert!(TypeParser::new().parse("i8388609").is_err());
+    assert!(TypeParser::new().parse("i99999999999999999999999").is_err());
+    assert!(ModuleParser::new().parse("@x = global i0 0").is_err());
 
     assert!(TypeParser::new().parse("half") == Ok(Type::FloatingPoint { kind: FloatingPointKind::Binary16 }));
     assert!(TypeParser::new().parse("bfloat") == Ok(Type::FloatingPoint { kind: FloatingPointKind::Brain }));
@@ -206,6 +220,25 @@ fn type_parsing() {
             })
     );
 
+    assert!(
+        TypeParser::new().parse("%struct.inode")
+            == Ok(Type::Identified {
+                name: "%struct.inode".to_string(),
+            })
+    );
+    assert!(
+        TypeParser::new().parse(r#"%"quoted name""#)
+            == Ok(Type::Identified {
+                name: "%quoted name".to_string(),
+            })
+    );
+    assert!(
+        TypeParser::new().parse("%0")
+            == Ok(Type::Identified {
+                name: "%0".to_string(),
+            })
+    );
+
     assert!(
         TypeParser::new().parse("i32 (i32)")
             == Ok(Type::Function {
@@ -236,3 +269,1440 @@ fn type_parsing() {
             })
     );
 }
+
+/// makes sure whole modules with several top-level entities, comments and blank lines can be parsed
+#[test]
+fn module_parsing() {
+    let module = ModuleParser::new()
+        .parse(
+            r#"; ModuleID = 'test.c'
+source_filename = "test.c"
+target datalayout = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-i128:128-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-pc-linux-gnu"
+
+$one = comdat any
+
+; a function
+define i32 @one() {
+  ret i32 1
+}
+
+define i32 @add(i32 %a, i32 %b) {
+entry:                                            ; the entry block
+  %0 = add i32 %a, %b
+  br label %exit
+
+exit:                                             ; preds = %entry
+  ret i32 %0
+}
+
+attributes #0 = { noinline nounwind optnone uwtable "frame-pointer"="all" }
+
+!llvm.module.flags = !{!0}
+!0 = !{i32 1, !"wchar_size", i32 4}
+"#,
+        )
+        .unwrap();
+
+    assert!(module.source_filename.as_deref() == Some("test.c"));
+    assert!(module.data_layout.as_deref() == Some("e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-i128:128-f80:128-n8:16:32:64-S128"));
+    assert!(module.target_triple.as_deref() == Some("x86_64-pc-linux-gnu"));
+    assert!(module.comdats.len() == 1);
+    assert!(module.comdats[0].name == "$one");
+
+    let functions = module.functions().collect::<Vec<_>>();
+    assert!(functions.len() == 2);
+    assert!(functions[0].header.name == "@one");
+    assert!(functions[1].header.name == "@add");
+    assert!(functions[1].basic_blocks.len() == 2);
+    assert!(functions[1].basic_blocks[0].name.as_deref() == Some("entry"));
+    assert!(functions[1].basic_blocks[1].name.as_deref() == Some("exit"));
+
+    assert!(ModuleParser::new().parse("").unwrap().symbols.is_empty());
+    assert!(ModuleParser::new().parse("\n; nothing here\n\n").unwrap().symbols.is_empty());
+}
+
+/// makes sure the optional parts of function headers end up in the right place
+#[test]
+fn function_header_parsing() {
+    let function = FunctionParser::new()
+        .parse(
+            r#"define dso_local noundef i32 @f(ptr noundef %x) #0 section ".text.f" align 16 {
+  %1 = alloca ptr, align 8
+  store ptr %x, ptr %1, align 8
+  ret i32 0
+}"#,
+        )
+        .unwrap();
+
+    assert!(matches!(function.header.linkage, LinkageType::External));
+    assert!(matches!(function.header.preemption_specifier, PreemptionSpecifier::Local));
+    assert!(matches!(function.header.return_type_parameter_attributes[..], [ParameterAttribute::NoUndefined]));
+    assert!(function.header.return_type == Type::Integer { bit_width: 32 });
+    assert!(function.header.name == "@f");
+    assert!(function.header.arguments.len() == 1);
+    assert!(matches!(function.header.arguments[0].attributes[..], [ParameterAttribute::NoUndefined]));
+    assert!(function.header.arguments[0].name.as_deref() == Some("%x"));
+    assert!(function.header.attribute_groups == vec![0]);
+    assert!(function.header.section_name.as_deref() == Some(".text.f"));
+    assert!(function.header.alignment == Some(16));
+    assert!(!function.header.has_varargs);
+
+    let function = FunctionParser::new()
+        .parse(
+            r#"define linkonce_odr hidden dllexport fastcc void @g(i32 signext %0, ptr nonnull align 8 %1, ...) local_unnamed_addr addrspace(1) #1 #2 comdat gc "shadow-stack" personality ptr @__gxx_personality_v0 {
+  ret void
+}"#,
+        )
+        .unwrap();
+
+    assert!(matches!(function.header.linkage, LinkageType::LinkOnceODR));
+    assert!(matches!(function.header.visibility, Visibility::Hidden));
+    assert!(matches!(function.header.dll_storage_class, DllStorageClass::Export));
+    assert!(function.header.calling_convention.as_deref() == Some("fastcc"));
+    assert!(function.header.arguments.len() == 2);
+    assert!(matches!(function.header.arguments[1].attributes[..], [ParameterAttribute::NonNull, ParameterAttribute::Alignment(8)]));
+    assert!(function.header.has_varargs);
+    assert!(matches!(function.header.unnamed_address, UnnamedAddress::Local));
+    assert!(function.header.address_space == Some(AddressSpace::Numbered(1)));
+    assert!(function.header.attribute_groups == vec![1, 2]);
+    assert!(function.header.comdat.as_deref() == Some("$g"));
+    assert!(function.header.garbage_collector.as_deref() == Some("shadow-stack"));
+    assert!(function.header.personality.is_some());
+
+    let function = FunctionParser::new().parse("define internal cc 10 void @h(i32) comdat($other) prefix i32 123 {\n  ret void\n}").unwrap();
+
+    assert!(function.header.calling_convention.as_deref() == Some("cc 10"));
+    assert!(function.header.arguments[0].name.is_none());
+    assert!(function.header.comdat.as_deref() == Some("$other"));
+    assert!(function.header.prefix.is_some());
+}
+
+/// makes sure declarations are parsed and share a symbol table with definitions
+#[test]
+fn function_declaration_parsing() {
+    let declaration = FunctionDeclarationParser::new().parse("declare i32 @reverse_word(i32)").unwrap();
+
+    assert!(declaration.name == "@reverse_word");
+    assert!(declaration.return_type == Type::Integer { bit_width: 32 });
+    assert!(declaration.arguments.len() == 1);
+    assert!(declaration.arguments[0].parameter_type == Type::Integer { bit_width: 32 });
+    assert!(declaration.arguments[0].name.is_none());
+
+    let declaration = FunctionDeclarationParser::new().parse("declare noundef i32 @printf(ptr noundef, ...) #1").unwrap();
+
+    assert!(declaration.has_varargs);
+    assert!(matches!(declaration.arguments[0].attributes[..], [ParameterAttribute::NoUndefined]));
+    assert!(declaration.attribute_groups == vec![1]);
+
+    let module = ModuleParser::new()
+        .parse(
+            r#"define i32 @get_inode_block_size(ptr %address) {
+  %i_size = call i32 @reverse_word(i32 0)
+  ret i32 %i_size
+}
+
+declare i32 @reverse_word(i32)
+"#,
+        )
+        .unwrap();
+
+    assert!(module.symbols.len() == 2);
+    assert!(matches!(module.get_symbol("@get_inode_block_size"), Some(Symbol::Function(_))));
+    assert!(matches!(module.get_symbol("@reverse_word"), Some(Symbol::FunctionDeclaration(_))));
+    assert!(module.get_symbol("@reverse_word").and_then(|s| s.function_header()).unwrap().arguments.len() == 1);
+    assert!(module.get_symbol("@block_size").is_none());
+
+    assert!(ModuleParser::new().parse("declare void @f()\ndefine void @f() {\n  ret void\n}").is_err());
+}
+
+/// makes sure global variable definitions are parsed correctly
+#[test]
+fn global_variable_parsing() {
+    let global = GlobalVariableParser::new().parse("@x = dso_local global i32 0, align 4").unwrap();
+
+    assert!(global.name == "@x");
+    assert!(matches!(global.preemption_specifier, PreemptionSpecifier::Local));
+    assert!(!global.is_constant);
+    assert!(global.value_type == Type::Integer { bit_width: 32 });
+    assert!(global.initializer.is_some());
+    assert!(global.alignment == Some(4));
+
+    let global = GlobalVariableParser::new().parse(r#"@str = private unnamed_addr constant [5 x i8] c"hello""#).unwrap();
+
+    assert!(matches!(global.linkage, LinkageType::Private));
+    assert!(matches!(global.unnamed_address, UnnamedAddress::Global));
+    assert!(global.is_constant);
+    assert!(global.value_type == Type::Array { length: 5, element_type: Box::new(Type::Integer { bit_width: 8 }) });
+
+    let global = GlobalVariableParser::new()
+        .parse(r#"@tls = internal thread_local(initialexec) addrspace(1) externally_initialized global ptr @x, section ".tdata", comdat, align 8"#)
+        .unwrap();
+
+    assert!(matches!(global.linkage, LinkageType::Internal));
+    assert!(matches!(global.thread_local, Some(ThreadLocalMode::InitialExec)));
+    assert!(global.address_space == Some(AddressSpace::Numbered(1)));
+    assert!(global.is_externally_initialized);
+    assert!(global.section_name.as_deref() == Some(".tdata"));
+    assert!(global.comdat.as_deref() == Some("$tls"));
+    assert!(global.alignment == Some(8));
+
+    let global = GlobalVariableParser::new().parse("@block_size = external global i32").unwrap();
+
+    assert!(matches!(global.linkage, LinkageType::External));
+    assert!(global.initializer.is_none());
+
+    let module = ModuleParser::new()
+        .parse(
+            r#"@block_size = external global i32
+
+define i32 @get_block_size() {
+  %block_size = load i32, ptr @block_size
+  ret i32 %block_size
+}
+"#,
+        )
+        .unwrap();
+
+    assert!(matches!(module.get_symbol("@block_size"), Some(Symbol::GlobalVariable(_))));
+    assert!(module.global_variables().count() == 1);
+    assert!(module.functions().count() == 1);
+}
+
+/// makes sure named types can be defined, referenced and resolved, and that they're distinct from literal structure types
+#[test]
+fn type_definition_parsing() {
+    let definition = TypeDefinitionParser::new().parse("%struct.pair = type { i32, i32 }").unwrap();
+
+    assert!(definition.name == "%struct.pair");
+    assert!(
+        definition.definition
+            == Type::Structure {
+                types: vec![Type::Integer { bit_width: 32 }, Type::Integer { bit_width: 32 }],
+                is_packed: false,
+            }
+    );
+
+    let definition = TypeDefinitionParser::new().parse("%struct.opaque = type opaque").unwrap();
+    assert!(definition.definition == Type::OpaqueStructure);
+
+    let module = ModuleParser::new()
+        .parse(
+            r#"%struct.node = type { i32, ptr, %struct.data }
+%struct.data = type { [4 x i8], ptr }
+%struct.pair = type { i32, i32 }
+%struct.other_pair = type { i32, i32 }
+%alias = type %struct.pair
+
+define i32 @first(ptr %node) {
+  %value = getelementptr %struct.node, ptr %node, i32 0, i32 0
+  %result = load i32, ptr %value
+  ret i32 %result
+}
+"#,
+        )
+        .unwrap();
+
+    assert!(module.type_definitions.len() == 5);
+
+    let node = Type::Identified {
+        name: "%struct.node".to_string(),
+    };
+    let pair = Type::Identified {
+        name: "%struct.pair".to_string(),
+    };
+    let other_pair = Type::Identified {
+        name: "%struct.other_pair".to_string(),
+    };
+    let alias = Type::Identified { name: "%alias".to_string() };
+
+    // named structures are only equal to themselves, even if they have the same definition as another type
+    assert!(pair != other_pair);
+    assert!(module.resolve_type(&pair) == module.resolve_type(&other_pair));
+    assert!(Some(&pair) != module.resolve_type(&pair));
+    assert!(module.resolve_type(&alias) == module.resolve_type(&pair));
+
+    match module.resolve_type(&node) {
+        Some(Type::Structure { types, .. }) => {
+            assert!(types[2] == Type::Identified { name: "%struct.data".to_string() });
+        }
+        _ => panic!("%struct.node should resolve to a structure"),
+    }
+
+    assert!(module.resolve_type(&Type::Identified { name: "%undefined".to_string() }).is_none());
+    assert!(ModuleParser::new().parse("%a = type { i32 }\n%a = type { i64 }").is_err());
+
+    let module = ModuleParser::new().parse("%recursive = type %recursive").unwrap();
+    assert!(module.resolve_type(&Type::Identified { name: "%recursive".to_string() }).is_none());
+}
+
+/// makes sure the types of values produced by instructions are inferred correctly
+#[test]
+fn value_types() {
+    let module = ModuleParser::new().parse("%struct.pair = type { i32, [2 x i64] }").unwrap();
+    let block = BasicBlockParser::new()
+        .parse(
+            r#"%sum = add i32 %a, 1
+%quotient = udiv exact <4 x i32> %x, %y
+%is_equal = icmp eq i64 %a, %b
+%are_equal = icmp ult <vscale x 4 x i32> %x, %y
+%loaded = load float, ptr %p
+%stack = alloca i32, align 4
+%other_stack = alloca i32, addrspace(5)
+%element = getelementptr inbounds %struct.pair, ptr %p, i32 0, i32 1
+%elements = getelementptr i32, ptr %p, <2 x i64> %offsets
+%first = extractvalue %struct.pair %pair, 0
+%second = extractvalue %struct.pair %pair, 1, 1
+%out_of_bounds = extractvalue %struct.pair %pair, 1, 2
+%inserted = insertvalue { i32, i64 } %s, i64 1, 1
+%truncated = trunc i64 %a to i32
+%pointer = inttoptr i64 %a to ptr
+%selected = select i1 %c, double %a, double %b
+%frozen = freeze i8 %a
+%called = call i32 @reverse_word(i32 %a)
+%called_with_type = call i64 (ptr, ...) @printf(ptr %format)
+store i32 %a, ptr %p
+ret { i32, i32 } { i32 1, i32 2 }"#,
+        )
+        .unwrap();
+
+    let types = block
+        .operations
+        .iter()
+        .map(|operation| match operation {
+            super::Operation::Assignment { value, .. } => value.get_result_type(&module),
+            super::Operation::NoAssignment { instruction, .. } => instruction.get_result_type(&module),
+        })
+        .collect::<Vec<_>>();
+
+    let i1 = Type::Integer { bit_width: 1 };
+    let i32 = Type::Integer { bit_width: 32 };
+    let i64 = Type::Integer { bit_width: 64 };
+    let ptr = Type::Pointer {
+        address_space: AddressSpace::Numbered(0),
+    };
+
+    assert!(types[0] == Some(i32.clone()));
+    assert!(
+        types[1]
+            == Some(Type::Vector {
+                length: 4,
+                element_type: Box::new(i32.clone()),
+                is_scalable: false,
+            })
+    );
+    assert!(types[2] == Some(i1.clone()));
+    assert!(
+        types[3]
+            == Some(Type::Vector {
+                length: 4,
+                element_type: Box::new(i1.clone()),
+                is_scalable: true,
+            })
+    );
+    assert!(types[4] == Some(Type::FloatingPoint { kind: FloatingPointKind::Binary32 }));
+    assert!(types[5] == Some(ptr.clone()));
+    assert!(
+        types[6]
+            == Some(Type::Pointer {
+                address_space: AddressSpace::Numbered(5),
+            })
+    );
+    assert!(types[7] == Some(ptr.clone()));
+    assert!(
+        types[8]
+            == Some(Type::Vector {
+                length: 2,
+                element_type: Box::new(ptr.clone()),
+                is_scalable: false,
+            })
+    );
+    assert!(types[9] == Some(i32.clone()));
+    assert!(types[10] == Some(i64.clone()));
+    assert!(types[11].is_none());
+    assert!(
+        types[12]
+            == Some(Type::Structure {
+                types: vec![i32.clone(), i64.clone()],
+                is_packed: false,
+            })
+    );
+    assert!(types[13] == Some(i32.clone()));
+    assert!(types[14] == Some(ptr.clone()));
+    assert!(types[15] == Some(Type::FloatingPoint { kind: FloatingPointKind::Binary64 }));
+    assert!(types[16] == Some(Type::Integer { bit_width: 8 }));
+    assert!(types[17] == Some(i32.clone()));
+    assert!(types[18] == Some(i64.clone()));
+    assert!(types[19] == Some(Type::Void));
+
+    match &block.terminator {
+        Terminator::Return { value } => assert!(
+            value.get_type(&())
+                == Some(Type::Structure {
+                    types: vec![i32.clone(), i32.clone()],
+                    is_packed: false,
+                })
+        ),
+        _ => panic!("block should end with a return"),
+    }
+
+    // extracting from an identified type doesn't work without its definition
+    let extracted = match &block.operations[9] {
+        super::Operation::Assignment { value, .. } => Value::FromInstruction { instruction: value.clone() },
+        _ => unreachable!(),
+    };
+    assert!(extracted.get_type(&module) == Some(i32));
+    assert!(extracted.get_type(&()).is_none());
+}
+
+/// makes sure parse errors point at the right place and describe what was expected in LLVM syntax
+#[test]
+fn parse_errors() {
+    let source = "define i32 @f() {\n\t%x = add i32 1, 2\n    %y = bogus i32 %x\n    ret i32 %y\n}";
+    let error = crate::error::ParseError::new(source, FunctionParser::new().parse(source).unwrap_err()).with_file_name("test.ll");
+
+    assert!(error.location == crate::error::SourceLocation { line: 3, column: 10 });
+    assert!(error.message == "invalid token `bogus`");
+    assert!(error.to_string() == "error: invalid token `bogus`\n --> test.ll:3:10\n  |\n3 |     %y = bogus i32 %x\n  |          ^^^^^\n");
+
+    // tabs are kept so the caret lines up
+    let source = "define i32 @f() {\n\t%x = ret i32 1\n}";
+    let error = crate::error::ParseError::new(source, FunctionParser::new().parse(source).unwrap_err());
+
+    assert!(error.message == "unexpected `ret`");
+    assert!(error.expected.contains(&"`add`".to_string()));
+    assert!(error.expected.contains(&"`getelementptr`".to_string()));
+    assert!(!error.expected.iter().any(|e| e.starts_with("`\"") || e.starts_with('/')));
+    assert!(error.to_string().starts_with("error: unexpected `ret`\n --> <input>:2:7\n  |\n2 | \t%x = ret i32 1\n  | \t     ^^^\n  = expected one of "));
+
+    // regular expressions are described instead of being shown directly
+    let source = "define i32 @f() {\n    ret i32 %y\n";
+    let error = crate::error::ParseError::new(source, FunctionParser::new().parse(source).unwrap_err());
+
+    assert!(error.message == "unexpected end of file");
+    assert!(error.location == crate::error::SourceLocation { line: 3, column: 1 });
+    assert!(error.expected.contains(&"newline".to_string()));
+    assert!(error.expected.contains(&"`}`".to_string()));
+
+    // errors found by the grammar's actions point at what they're about too
+    let source = "@x = global i32 0\n@y = global i32 1.5\n";
+    let error = crate::error::ParseError::new(source, ModuleParser::new().parse(source).unwrap_err());
+    assert!(error.to_string() == "error: constant is incompatible with its type\n --> <input>:2:17\n  |\n2 | @y = global i32 1.5\n  |                 ^^^\n");
+
+    let source = "@x = global i32 0\n@x = global i64 0\n";
+    let error = crate::error::ParseError::new(source, ModuleParser::new().parse(source).unwrap_err());
+    assert!(error.message == "invalid redefinition of symbol");
+    assert!(error.location == crate::error::SourceLocation { line: 2, column: 1 } && error.length == 17);
+
+    let source = "@x = global i9999999 0";
+    let error = crate::error::ParseError::new(source, ModuleParser::new().parse(source).unwrap_err());
+    assert!(error.location == crate::error::SourceLocation { line: 1, column: 13 } && error.length == 8);
+}
+
+/// makes sure printed IR parses back into the same thing, and that printing is stable
+#[test]
+fn round_trip() {
+    let source = r#"; a module with at least one of most things in it
+source_filename = "test.c"
+target datalayout = "e-m:e-i64:64-n32:64"
+target triple = "x86_64-pc-linux-gnu"
+
+$f = comdat any
+%pair = type { i32, ptr addrspace(1) }
+%packed = type <{ i8, <vscale x 4 x i16> }>
+%opaque = type opaque
+%"quoted name" = type [4 x %pair]
+
+@x = dso_local global i32 -1, align 4
+@str = private unnamed_addr constant [6 x i8] c"hello!", section "rodata"
+@bytes = internal constant [2 x i8] [i8 0, i8 1]
+@empty = constant [0 x i8] c""
+@tls = thread_local(initialexec) externally_initialized global ptr null
+@p = global %pair { i32 1, ptr addrspace(1) null }, comdat($f)
+@u = hidden global <2 x i64> <i64 undef, i64 poison>
+@ext = external global double
+@"quoted global" = weak_odr global { i1, float } zeroinitializer
+
+declare noundef i32 @g(ptr nonnull, ...) #0
+declare void @h()
+
+define internal fastcc zeroext i8 @f(i32 %a, ptr byval(%pair) align 8 %b) unnamed_addr #1 #2 section "text" comdat($f) align 16 gc "shadow-stack" prefix i32 1 {
+entry:
+    %0 = add nuw nsw i32 %a, 1
+    %1 = sub i32 2, %0
+    %c = udiv exact i32 %0, %1
+    %d = or disjoint i32 %c, %a
+    %e = alloca inalloca %pair, i32 4, align 8, addrspace(1)
+    %f = load volatile i32, ptr %b, align 4
+    %g = load atomic i32, ptr %b syncscope("agent") acquire, align 4
+    store atomic volatile i32 %g, ptr %b release, align 4
+    fence seq_cst
+    %h = getelementptr inbounds %pair, ptr %b, i64 0, i32 1
+    %i = trunc nsw i32 %a to i8
+    %j = icmp sle i32 %a, -5
+    %k = select i1 %j, i8 %i, i8 0
+    %l = extractvalue %pair { i32 1, ptr addrspace(1) null }, 0
+    %m = insertvalue { i32, i32 } undef, i32 %l, 1
+    %n = tail call i32 (ptr, ...) @g(ptr %b, i32 %a)
+    call void @h()
+    %o = call i32 asm sideeffect "mov $0, 1", "=r"()
+    switch i32 %n, label %exit [ i32 0, label %entry2 i32 1, label %exit ]
+
+entry2:
+    br i1 %j, label %exit, label %exit
+
+exit:
+    ret i8 %k
+}
+"#;
+
+    let module = ModuleParser::new().parse(source).unwrap();
+    let printed = module.to_string();
+    let reparsed = ModuleParser::new().parse(&printed).unwrap();
+
+    assert!(module == reparsed);
+    assert!(reparsed.to_string() == printed);
+
+    assert!(printed.contains("@x = dso_local global i32 -1, align 4\n"));
+    assert!(printed.contains("@ext = external global double\n"));
+    assert!(printed.contains("zeroinitializer\n\ndeclare noundef i32 @g(ptr nonnull, ...) #0\n"));
+    assert!(printed.contains("@str = private unnamed_addr constant [6 x i8] c\"hello!\", section \"rodata\"\n"));
+    assert!(printed.contains("@bytes = internal constant [2 x i8] c\"\\00\\01\"\n"));
+    assert!(printed.contains("%\"quoted name\" = type [4 x %pair]\n"));
+    assert!(printed.contains("    %n = tail call i32 (ptr, ...) @g(ptr %b, i32 %a)\n"));
+    assert!(printed.contains("\n\nexit:\n    ret i8 %k\n}\n"));
+    assert!(TypeParser::new().parse("<{ i8, <vscale x 4 x i16> }>").unwrap().to_string() == "<{ i8, <vscale x 4 x i16> }>");
+}
+
+/// makes sure floating point arithmetic is parsed along with its fast-math flags
+#[test]
+fn float_arithmetic() {
+    let block = BasicBlockParser::new()
+        .parse(
+            r#"%a = fadd float %x, %y
+%b = fsub nnan ninf double %x, %y
+%c = fmul fast <4 x float> %x, %y
+%d = fdiv nsz arcp contract afn reassoc half %x, %y
+%e = frem reassoc nnan fp128 %x, %y
+%f = fneg ninf float %x
+%g = select nnan i1 %c, float %x, float %y
+%h = call fast float @sqrtf(float %x)
+ret void"#,
+        )
+        .unwrap();
+
+    let flags = block
+        .operations
+        .iter()
+        .map(|operation| match operation {
+            super::Operation::Assignment {
+                value:
+                    Instruction::FloatAdd { fast_math_flags, .. }
+                    | Instruction::FloatSubtract { fast_math_flags, .. }
+                    | Instruction::FloatMultiply { fast_math_flags, .. }
+                    | Instruction::FloatDivide { fast_math_flags, .. }
+                    | Instruction::FloatRemainder { fast_math_flags, .. }
+                    | Instruction::FloatNegate { fast_math_flags, .. }
+                    | Instruction::Select { fast_math_flags, .. }
+                    | Instruction::Call { fast_math_flags, .. },
+                ..
+            } => *fast_math_flags,
+            _ => panic!("unexpected operation {operation:?}"),
+        })
+        .collect::<Vec<_>>();
+
+    assert!(flags[0] == FastMathFlags::default());
+    assert!(
+        flags[1]
+            == FastMathFlags {
+                no_nans: true,
+                no_infinities: true,
+                ..Default::default()
+            }
+    );
+    assert!(flags[2] == FastMathFlags::FAST);
+    // every flag at once is the same as `fast`
+    assert!(flags[3] == FastMathFlags { no_nans: false, no_infinities: false, ..FastMathFlags::FAST });
+    assert!(
+        flags[4]
+            == FastMathFlags {
+                no_nans: true,
+                allow_reassociation: true,
+                ..Default::default()
+            }
+    );
+    assert!(flags[5] == FastMathFlags { no_infinities: true, ..Default::default() });
+    assert!(flags[6] == FastMathFlags { no_nans: true, ..Default::default() });
+    assert!(flags[7] == FastMathFlags::FAST);
+
+    assert!(block.to_string() == "    %a = fadd float %x, %y\n    %b = fsub nnan ninf double %x, %y\n    %c = fmul fast <4 x float> %x, %y\n    %d = fdiv nsz arcp contract afn reassoc half %x, %y\n    %e = frem nnan reassoc fp128 %x, %y\n    %f = fneg ninf float %x\n    %g = select nnan i1 %c, float %x, float %y\n    %h = call fast float @sqrtf(float %x)\n    ret void");
+
+    let types = block.operations.iter().map(|operation| match operation {
+        super::Operation::Assignment { value, .. } => value.get_result_type(&()),
+        _ => unreachable!(),
+    });
+    assert!(types.eq([
+        Some(Type::FloatingPoint { kind: FloatingPointKind::Binary32 }),
+        Some(Type::FloatingPoint { kind: FloatingPointKind::Binary64 }),
+        Some(Type::Vector {
+            length: 4,
+            element_type: Box::new(Type::FloatingPoint { kind: FloatingPointKind::Binary32 }),
+            is_scalable: false,
+        }),
+        Some(Type::FloatingPoint { kind: FloatingPointKind::Binary16 }),
+        Some(Type::FloatingPoint { kind: FloatingPointKind::Binary128 }),
+        Some(Type::FloatingPoint { kind: FloatingPointKind::Binary32 }),
+        Some(Type::FloatingPoint { kind: FloatingPointKind::Binary32 }),
+        Some(Type::FloatingPoint { kind: FloatingPointKind::Binary32 }),
+    ]));
+}
+
+/// makes sure every spelling of floating point constants is converted to the format of its type, and that constants which don't fit are rejected
+#[test]
+fn float_constants() {
+    let source = r#"@a = global double 1.5e+10
+@b = global float 0x3FB99999A0000000
+@c = global half 0xH3C00
+@d = global bfloat 0xR3F80
+@e = global x86_fp80 0xK3FFF8000000000000000
+@f = global fp128 0xL00000000000000003FFF000000000000
+@g = global ppc_fp128 0xM3FF00000000000000000000000000000
+@h = global half 1.0
+@i = global fp128 2.5
+@j = global x86_fp80 -3.0
+@k = global half 0.000000059604644775390625
+@l = global float 0x7FF8000000000000
+@m = global double -0.0
+"#;
+    let module = ModuleParser::new().parse(source).unwrap();
+    let values = module.global_variables().map(|global| match global.initializer.as_deref() {
+        Some(Value::FromConstant {
+            constant: Constant::FloatingPoint(value),
+            ..
+        }) => (value.kind, value.bits),
+        initializer => panic!("unexpected initializer {initializer:?}"),
+    });
+
+    assert!(values.eq([
+        (FloatingPointKind::Binary64, 1.5e10f64.to_bits().into()),
+        (FloatingPointKind::Binary32, 0.1f32.to_bits().into()),
+        (FloatingPointKind::Binary16, 0x3c00),
+        (FloatingPointKind::Brain, 0x3f80),
+        (FloatingPointKind::X86Fp80, 0x3fff_8000000000000000),
+        (FloatingPointKind::Binary128, 0x3fff << 112),
+        (FloatingPointKind::PpcFp128, 1f64.to_bits().into()),
+        (FloatingPointKind::Binary16, 0x3c00),
+        (FloatingPointKind::Binary128, 0x40004 << 108),
+        (FloatingPointKind::X86Fp80, 0xc000_c000000000000000),
+        (FloatingPointKind::Binary16, 1),
+        (FloatingPointKind::Binary32, 0x7fc00000),
+        (FloatingPointKind::Binary64, (-0f64).to_bits().into()),
+    ]));
+
+    // floats and doubles are printed in decimal unless they aren't finite, and everything else is printed in its own hexadecimal format
+    let printed = module.to_string();
+    assert!(printed.contains("@a = global double 1.5e+10\n"));
+    assert!(printed.contains("@b = global float 1.0000000149011612e-01\n"));
+    assert!(printed.contains("@h = global half 0xH3C00\n"));
+    assert!(printed.contains("@l = global float 0x7FF8000000000000\n"));
+    assert!(printed.contains("@m = global double -0.0e+00\n"));
+    assert!(ModuleParser::new().parse(&printed).unwrap() == module);
+
+    // these can't be represented exactly, or are written in the format of a different type
+    for source in ["@x = global float 0.1", "@x = global half 65536.0", "@x = global float 1.0e-50", "@x = global double 0xH3C00", "@x = global half 0xH10000", "@x = global i32 1.0"] {
+        assert!(matches!(ModuleParser::new().parse(source), Err(lalrpop_util::ParseError::User { .. })), "{source}");
+    }
+
+    // subnormal doubles are normal in wider formats
+    let smallest = FloatingPointValue::from_f64(f64::from_bits(1));
+    assert!(smallest.convert(FloatingPointKind::Binary128) == Some(FloatingPointValue { kind: FloatingPointKind::Binary128, bits: (16383 - 1074) << 112 }));
+    assert!(smallest.convert(FloatingPointKind::X86Fp80).unwrap().bits == (16383 - 1074) << 64 | 1 << 63);
+}
+
+/// makes sure fcmp takes every predicate, and that conversions to and from floating point types produce the right types
+#[test]
+fn float_comparisons_and_conversions() {
+    let predicates = ["false", "oeq", "ogt", "oge", "olt", "ole", "one", "ord", "ueq", "ugt", "uge", "ult", "ule", "une", "uno", "true"];
+    let source = predicates.iter().enumerate().map(|(i, predicate)| format!("%c{i} = fcmp {predicate} float %x, 1.0\n")).collect::<String>() + "ret void";
+    let block = BasicBlockParser::new().parse(&source).unwrap();
+
+    let comparisons = block.operations.iter().map(|operation| match operation {
+        super::Operation::Assignment {
+            value: Instruction::CompareFloats { comparison, .. },
+            ..
+        } => *comparison,
+        _ => panic!("unexpected operation {operation:?}"),
+    });
+    assert!(comparisons.eq([
+        FloatComparison::False,
+        FloatComparison::OrderedEqual,
+        FloatComparison::OrderedGreaterThan,
+        FloatComparison::OrderedGreaterOrEqual,
+        FloatComparison::OrderedLessThan,
+        FloatComparison::OrderedLessOrEqual,
+        FloatComparison::OrderedNotEqual,
+        FloatComparison::Ordered,
+        FloatComparison::UnorderedEqual,
+        FloatComparison::UnorderedGreaterThan,
+        FloatComparison::UnorderedGreaterOrEqual,
+        FloatComparison::UnorderedLessThan,
+        FloatComparison::UnorderedLessOrEqual,
+        FloatComparison::UnorderedNotEqual,
+        FloatComparison::Unordered,
+        FloatComparison::True,
+    ]));
+
+    let source = r#"%a = fcmp nnan oeq <4 x double> %x, %y
+%b = fptrunc double %x to float
+%c = fpext fast <2 x half> %x to <2 x double>
+%d = fptoui float %x to i32
+%e = fptosi <4 x double> %x to <4 x i8>
+%f = uitofp i64 %x to half
+%g = sitofp <vscale x 2 x i32> %x to <vscale x 2 x float>
+ret void"#;
+    let block = BasicBlockParser::new().parse(source).unwrap();
+    assert!(block.to_string() == source.lines().map(|line| format!("    {line}")).collect::<Vec<_>>().join("\n"));
+
+    let types = block.operations.iter().map(|operation| match operation {
+        super::Operation::Assignment { value, .. } => value.get_result_type(&()),
+        _ => unreachable!(),
+    });
+    let vector = |length, element_type, is_scalable| Type::Vector {
+        length,
+        element_type: Box::new(element_type),
+        is_scalable,
+    };
+    assert!(types.eq([
+        Some(vector(4, Type::Integer { bit_width: 1 }, false)),
+        Some(Type::FloatingPoint { kind: FloatingPointKind::Binary32 }),
+        Some(vector(2, Type::FloatingPoint { kind: FloatingPointKind::Binary64 }, false)),
+        Some(Type::Integer { bit_width: 32 }),
+        Some(vector(4, Type::Integer { bit_width: 8 }, false)),
+        Some(Type::FloatingPoint { kind: FloatingPointKind::Binary16 }),
+        Some(vector(2, Type::FloatingPoint { kind: FloatingPointKind::Binary32 }, true)),
+    ]));
+}
+
+/// makes sure phi nodes pair each incoming value with the block it comes from, giving untyped values the type of the phi
+#[test]
+fn phi_parsing() {
+    let block = BasicBlockParser::new().parse("%x = phi i32 [ 0, %entry ], [ %next, %loop ]\n%y = phi nnan double [ 1.5, %0 ]\nret void").unwrap();
+
+    let super::Operation::Assignment {
+        value: Instruction::Phi {
+            fast_math_flags,
+            value_type,
+            incoming,
+        },
+        ..
+    } = &block.operations[0]
+    else {
+        panic!("expected a phi node, got {:?}", block.operations[0]);
+    };
+    let label = |identifier: &str| {
+        Arc::new(Value::FromIdentifier {
+            value_type: Type::Label,
+            identifier: identifier.to_string(),
+        })
+    };
+
+    assert!(*fast_math_flags == FastMathFlags::default());
+    assert!(*value_type == Type::Integer { bit_width: 32 });
+    assert!(
+        *incoming
+            == [
+                IncomingValue {
+                    value: Arc::new(Value::from_type_constant(Type::Integer { bit_width: 32 }, Constant::Integer(Integer::zero(32))).unwrap()),
+                    block: label("%entry"),
+                },
+                IncomingValue {
+                    value: Arc::new(Value::FromIdentifier {
+                        value_type: Type::Integer { bit_width: 32 },
+                        identifier: "%next".to_string(),
+                    }),
+                    block: label("%loop"),
+                },
+            ]
+    );
+
+    assert!(block.to_string() == "    %x = phi i32 [ 0, %entry ], [ %next, %loop ]\n    %y = phi nnan double [ 1.5e+00, %0 ]\n    ret void");
+    assert!(BasicBlockParser::new().parse("%x = phi i32 [ 1.0, %a ]\nret void").is_err());
+}
+
+/// makes sure cmpxchg and every atomicrmw operation are parsed with their flags, orderings and sync scopes
+#[test]
+fn atomic_instructions() {
+    let source = r#"%a = cmpxchg ptr %p, i32 0, i32 1 acquire monotonic
+%b = cmpxchg weak volatile ptr %p, ptr null, ptr %q syncscope("agent") seq_cst acquire, align 8
+%c = atomicrmw volatile uinc_wrap ptr %p, i32 10 syncscope("singlethread") monotonic, align 4
+ret void"#;
+    let block = BasicBlockParser::new().parse(source).unwrap();
+    assert!(block.to_string() == source.lines().map(|line| format!("    {line}")).collect::<Vec<_>>().join("\n"));
+
+    let super::Operation::Assignment {
+        value: exchange @ Instruction::CompareExchange {
+            is_weak,
+            is_volatile,
+            sync_scope,
+            success_ordering,
+            failure_ordering,
+            alignment,
+            ..
+        },
+        ..
+    } = &block.operations[1]
+    else {
+        panic!("expected cmpxchg, got {:?}", block.operations[1]);
+    };
+    assert!(*is_weak && *is_volatile);
+    assert!(sync_scope.as_deref() == Some("agent"));
+    assert!(*success_ordering == Ordering::SequentiallyConsistent && *failure_ordering == Ordering::Acquire);
+    assert!(*alignment == Some(8));
+    assert!(
+        exchange.get_result_type(&())
+            == Some(Type::Structure {
+                types: vec![Type::Pointer { address_space: AddressSpace::Numbered(0) }, Type::Integer { bit_width: 1 }],
+                is_packed: false,
+            })
+    );
+
+    let operations = [
+        "xchg",
+        "add",
+        "sub",
+        "and",
+        "nand",
+        "or",
+        "xor",
+        "max",
+        "min",
+        "umax",
+        "umin",
+        "fadd",
+        "fsub",
+        "fmax",
+        "fmin",
+        "uinc_wrap",
+        "udec_wrap",
+    ];
+    let source = operations.iter().map(|operation| format!("atomicrmw {operation} ptr %p, i64 1 seq_cst\n")).collect::<String>() + "ret void";
+    let block = BasicBlockParser::new().parse(&source).unwrap();
+
+    let parsed = block.operations.iter().map(|operation| match operation {
+        super::Operation::NoAssignment {
+            instruction: instruction @ Instruction::AtomicReadModifyWrite { operation, .. },
+            ..
+        } => {
+            assert!(instruction.get_result_type(&()) == Some(Type::Integer { bit_width: 64 }));
+            *operation
+        }
+        _ => panic!("unexpected operation {operation:?}"),
+    });
+    assert!(parsed.eq([
+        AtomicOperation::Exchange,
+        AtomicOperation::Add,
+        AtomicOperation::Subtract,
+        AtomicOperation::And,
+        AtomicOperation::Nand,
+        AtomicOperation::Or,
+        AtomicOperation::ExclusiveOr,
+        AtomicOperation::SignedMax,
+        AtomicOperation::SignedMin,
+        AtomicOperation::UnsignedMax,
+        AtomicOperation::UnsignedMin,
+        AtomicOperation::FloatAdd,
+        AtomicOperation::FloatSubtract,
+        AtomicOperation::FloatMax,
+        AtomicOperation::FloatMin,
+        AtomicOperation::UnsignedIncrementWrap,
+        AtomicOperation::UnsignedDecrementWrap,
+    ]));
+    assert!(block.to_string().lines().zip(source.lines()).all(|(printed, line)| printed.trim() == line));
+}
+
+/// makes sure vector element instructions are parsed with poison, undef and zeroinitializer masks, and produce the right types
+#[test]
+fn vector_element_instructions() {
+    let source = r#"%a = extractelement <4 x i32> %v, i32 0
+%b = insertelement <4 x float> %w, float 1.5e+00, i64 %i
+%c = shufflevector <4 x i32> %v, <4 x i32> poison, <2 x i32> <i32 3, i32 poison>
+%d = shufflevector <4 x i32> %v, <4 x i32> %v, <8 x i32> <i32 0, i32 undef, i32 2, i32 3, i32 4, i32 5, i32 6, i32 7>
+%e = shufflevector <vscale x 4 x i32> %s, <vscale x 4 x i32> undef, <vscale x 4 x i32> zeroinitializer
+%f = extractelement <vscale x 2 x ptr> %p, i32 %i
+ret void"#;
+    let block = BasicBlockParser::new().parse(source).unwrap();
+    assert!(block.to_string() == source.lines().map(|line| format!("    {line}")).collect::<Vec<_>>().join("\n"));
+
+    let super::Operation::Assignment {
+        value: Instruction::ShuffleVector { mask, .. },
+        ..
+    } = &block.operations[2]
+    else {
+        panic!("expected shufflevector, got {:?}", block.operations[2]);
+    };
+    let mask_element = |constant| Arc::new(Value::FromConstant { constant_type: Type::Integer { bit_width: 32 }, constant });
+    assert!(
+        **mask
+            == Value::FromConstant {
+                constant_type: Type::Vector {
+                    length: 2,
+                    element_type: Box::new(Type::Integer { bit_width: 32 }),
+                    is_scalable: false,
+                },
+                constant: Constant::Vector(vec![mask_element(Constant::Integer(Integer::from_u64(32, 3))), mask_element(Constant::Poison)]),
+            }
+    );
+
+    let types = block.operations.iter().map(|operation| match operation {
+        super::Operation::Assignment { value, .. } => value.get_result_type(&()),
+        _ => unreachable!(),
+    });
+    let vector = |length, element_type, is_scalable| Type::Vector {
+        length,
+        element_type: Box::new(element_type),
+        is_scalable,
+    };
+    assert!(types.eq([
+        Some(Type::Integer { bit_width: 32 }),
+        Some(vector(4, Type::FloatingPoint { kind: FloatingPointKind::Binary32 }, false)),
+        Some(vector(2, Type::Integer { bit_width: 32 }, false)),
+        Some(vector(8, Type::Integer { bit_width: 32 }, false)),
+        Some(vector(4, Type::Integer { bit_width: 32 }, true)),
+        Some(Type::Pointer { address_space: AddressSpace::Numbered(0) }),
+    ]));
+}
+
+/// makes sure exception handling is parsed the way clang formats it, with landingpad clauses and invoke destinations on their own lines
+#[test]
+fn exception_handling() {
+    let source = r#"define void @f() personality ptr @__gxx_personality_v0 {
+entry:
+  %r = invoke i32 @g(i32 1)
+          to label %ok unwind label %lpad
+
+ok:
+  callbr void asm "jmp ${0:l}", "!i"()
+          to label %done [label %lpad2]
+
+lpad:
+  %lp = landingpad { ptr, i32 }
+          cleanup
+          catch ptr @typeinfo
+          filter [1 x ptr] [ptr @typeinfo]
+  resume { ptr, i32 } %lp
+
+lpad2:
+  %cs = catchswitch within none [label %handler] unwind to caller
+
+handler:
+  %cp = catchpad within %cs [ptr null, i32 64, ptr null]
+  catchret from %cp to label %cleanup
+
+cleanup:
+  %cl = cleanuppad within none []
+  cleanupret from %cl unwind label %lpad2
+
+done:
+  ret void
+}"#;
+    let function = FunctionParser::new().parse(source).unwrap();
+    let printed = function.to_string();
+
+    assert!(FunctionParser::new().parse(&printed).unwrap() == function);
+    assert!(printed.contains("    %r = invoke i32 @g(i32 1) to label %ok unwind label %lpad\n"));
+    assert!(printed.contains("    callbr void asm \"jmp ${0:l}\", \"!i\"() to label %done [label %lpad2]\n"));
+    assert!(printed.contains("    %lp = landingpad { ptr, i32 } cleanup catch ptr @typeinfo filter [1 x ptr] [ptr @typeinfo]\n"));
+    assert!(printed.contains("    %cs = catchswitch within none [label %handler] unwind to caller\n"));
+    assert!(printed.contains("    catchret from %cp to label %cleanup\n"));
+    assert!(printed.contains("    cleanupret from %cl unwind label %lpad2\n"));
+
+    let entry = &function.basic_blocks[0];
+    assert!(entry.terminator_identifier.as_deref() == Some("%r"));
+    assert!(entry.terminator.get_result_type() == Type::Integer { bit_width: 32 });
+    assert!(function.basic_blocks[3].terminator.get_result_type() == Type::Token);
+
+    let super::Operation::Assignment {
+        value: Instruction::LandingPad { is_cleanup, clauses, .. },
+        ..
+    } = &function.basic_blocks[2].operations[0]
+    else {
+        panic!("expected landingpad, got {:?}", function.basic_blocks[2].operations[0]);
+    };
+    assert!(*is_cleanup);
+    assert!(matches!(clauses[..], [LandingPadClause::Catch(_), LandingPadClause::Filter(_)]));
+
+    let Terminator::CatchSwitch { parent, unwind_destination, .. } = &function.basic_blocks[3].terminator else {
+        panic!("expected catchswitch, got {:?}", function.basic_blocks[3].terminator);
+    };
+    assert!(**parent == Value::FromConstant { constant_type: Type::Token, constant: Constant::NoneToken });
+    assert!(unwind_destination.is_none());
+}
+
+/// makes sure constant expressions can be nested inside globals and instructions, printed back the same way and folded
+#[test]
+fn constant_expressions() {
+    let source = r#"@arr = global [4 x i32] zeroinitializer
+@p = global ptr getelementptr inbounds ([4 x i32], ptr @arr, i64 0, i64 2)
+@i = global i64 ptrtoint (ptr @arr to i64)
+@n = global i64 add nuw (i64 ptrtoint (ptr @arr to i64), i64 sub (i64 8, i64 4))
+@c = global i1 icmp ult (ptr @arr, ptr getelementptr (i8, ptr @arr, i64 1))
+"#;
+    let module = ModuleParser::new().parse(source).unwrap();
+    assert!(module.to_string() == source);
+
+    let Symbol::GlobalVariable(n) = &module.symbols[3] else {
+        panic!("expected a global variable, got {:?}", module.symbols[3]);
+    };
+    let Some(Value::FromConstant {
+        constant: Constant::Expression(add),
+        ..
+    }) = n.initializer.as_deref()
+    else {
+        panic!("expected a constant expression, got {:?}", n.initializer);
+    };
+    assert!(matches!(**add, Instruction::Add { .. }));
+    // the address of a global isn't known yet, but the subtraction inside it can be folded
+    assert!(add.evaluate(&()).is_none());
+    assert!(add.operands()[1].evaluate(&()) == Some(Constant::Integer(Integer::from_u64(64, 4))));
+
+    let block = BasicBlockParser::new()
+        .parse("%a = add i32 mul (i32 3, i32 select (i1 true, i32 5, i32 7)), 1\nret i32 %a")
+        .unwrap();
+    assert!(block.to_string() == "    %a = add i32 mul (i32 3, i32 select (i1 true, i32 5, i32 7)), 1\n    ret i32 %a");
+
+    let evaluate = |source: &str| match ModuleParser::new().parse(&format!("@x = global {source}\n")).unwrap().symbols[0] {
+        Symbol::GlobalVariable(ref variable) => variable.initializer.as_ref().unwrap().evaluate(&()),
+        _ => unreachable!(),
+    };
+    let integer = |bit_width, value| Some(Constant::Integer(Integer::from_i64(bit_width, value)));
+    assert!(evaluate("i8 add (i8 127, i8 1)") == integer(8, -128));
+    assert!(evaluate("i8 add nsw (i8 127, i8 1)") == Some(Constant::Poison));
+    assert!(evaluate("i32 udiv (i32 7, i32 0)") == Some(Constant::Poison));
+    assert!(evaluate("i32 sdiv (i32 -7, i32 2)") == integer(32, -3));
+    assert!(evaluate("i32 lshr (i32 -1, i32 28)") == integer(32, 15));
+    assert!(evaluate("i32 ashr exact (i32 -8, i32 2)") == integer(32, -2));
+    assert!(evaluate("i1 icmp slt (i16 -1, i16 0)") == Some(Constant::Boolean(true)));
+    assert!(evaluate("i1 icmp ult (i16 -1, i16 0)") == Some(Constant::Boolean(false)));
+    assert!(evaluate("i8 trunc (i32 300 to i8)") == integer(8, 44));
+    assert!(evaluate("i8 trunc nuw (i32 300 to i8)") == Some(Constant::Poison));
+    assert!(evaluate("i64 zext (i8 -1 to i64)") == integer(64, 255));
+    assert!(evaluate("i64 sext (i8 -1 to i64)") == integer(64, -1));
+    assert!(evaluate("i32 extractelement (<2 x i32> <i32 4, i32 9>, i32 1)") == integer(32, 9));
+    assert!(evaluate("i32 select (i1 false, i32 poison, i32 3)") == integer(32, 3));
+    assert!(evaluate("i64 ptrtoint (ptr @x to i64)").is_none());
+}
+
+/// makes sure integer constants of any width are parsed exactly, given the value LLVM gives them and printed back as signed numbers
+#[test]
+fn integer_constants() {
+    let source = r#"@a = global i128 170141183460469231731687303715884105727
+@b = global i128 -170141183460469231731687303715884105728
+@c = global i1942652 0
+@d = global i256 -1
+@e = global i1 true
+"#;
+    let module = ModuleParser::new().parse(source).unwrap();
+    assert!(module.to_string() == source);
+
+    let constant = |source: &str| match ModuleParser::new().parse(&format!("@x = global {source}\n")).unwrap().symbols[0] {
+        Symbol::GlobalVariable(ref variable) => match variable.initializer.as_deref() {
+            Some(Value::FromConstant { constant, .. }) => constant.clone(),
+            other => panic!("expected a constant, got {other:?}"),
+        },
+        _ => unreachable!(),
+    };
+    let integer = |bit_width, value| Constant::Integer(Integer::from_i64(bit_width, value));
+
+    // the most significant bit that's set in a signed hexadecimal literal is its sign bit
+    assert!(constant("i32 s0xFF") == integer(32, -1));
+    assert!(constant("i32 s0x0F") == integer(32, -1));
+    assert!(constant("i32 s0x7F") == integer(32, -1));
+    assert!(constant("i32 u0xFF") == integer(32, 255));
+    assert!(constant("i8 255") == integer(8, -1));
+    assert!(constant("i8 300") == integer(8, 44));
+    assert!(constant("i65 -1") == Constant::Integer(Integer::all_ones(65)));
+    assert!(constant("i128 u0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF") == Constant::Integer(Integer::all_ones(128)));
+    assert!(constant("i128 18446744073709551616") == Constant::Integer(Integer::from_u64(128, 1).shift_left(64)));
+
+    // folding works on any width too
+    let fold = |source: &str| match ModuleParser::new().parse(&format!("@x = global {source}\n")).unwrap().symbols[0] {
+        Symbol::GlobalVariable(ref variable) => Value::FromConstant {
+            constant_type: variable.value_type.clone(),
+            constant: variable.initializer.as_ref().unwrap().evaluate(&()).unwrap(),
+        }
+        .to_string(),
+        _ => unreachable!(),
+    };
+    assert!(fold("i128 mul (i128 18446744073709551616, i128 -3)") == "i128 -55340232221128654848");
+    assert!(fold("i128 mul nsw (i128 85070591730234615865843651857942052864, i128 2)") == "i128 poison");
+    assert!(fold("i128 udiv (i128 -1, i128 18446744073709551616)") == "i128 18446744073709551615");
+    assert!(fold("i128 sdiv (i128 -55340232221128654848, i128 36893488147419103232)") == "i128 -1");
+    assert!(fold("i128 srem (i128 -55340232221128654848, i128 36893488147419103232)") == "i128 -18446744073709551616");
+    assert!(fold("i100 ashr (i100 -633825300114114700748351602688, i100 90)") == "i100 -512");
+    assert!(fold("i100 lshr exact (i100 3, i100 1)") == "i100 poison");
+    assert!(fold("i1 icmp slt (i128 -1, i128 0)") == "i1 true");
+}
+
+/// makes sure escape sequences in quoted names and strings are decoded, and written back out the same way.
+/// unlike in LLVM, quoted names and strings have to decode to valid UTF-8 since they're stored as `String`s
+#[test]
+fn string_escapes() {
+    assert!(super::parse_escape_sequences(r"a\\b\0Ac\4") == b"a\\b\nc\\4");
+    assert!(super::parse_escape_sequences(r"\ff\FF\5C") == [0xFF, 0xFF, b'\\']);
+
+    let source = r#"@"\01foo" = global [4 x i8] c"hi\0A\00"
+@"with space" = global [3 x i8] c"\22\5C\FF"
+@"\E2\9C\93" = global i8 0
+"#;
+    let module = ModuleParser::new().parse(source).unwrap();
+    let names = module
+        .symbols
+        .iter()
+        .map(|symbol| match symbol {
+            Symbol::GlobalVariable(variable) => variable.name.as_str(),
+            _ => unreachable!(),
+        })
+        .collect::<Vec<_>>();
+    assert!(names == ["@\u{1}foo", "@with space", "@\u{2713}"]);
+    assert!(module.to_string() == source);
+
+    // names have to decode to valid UTF-8, but c strings can hold any bytes
+    let source = "@x = global i8 0\n@\"\\FF\" = global i8 0\n";
+    let error = crate::error::ParseError::new(source, ModuleParser::new().parse(source).unwrap_err());
+    assert!(error.message == "strings must be valid UTF-8");
+    assert!(error.location == crate::error::SourceLocation { line: 2, column: 2 } && error.length == 5);
+}
+
+/// makes sure function attributes are parsed on functions, calls and attribute groups, and are printed back out the same way
+#[test]
+fn function_attributes() {
+    let source = r#"declare ptr @malloc(i64) #1
+
+define dso_local void @f(ptr noundef %p) nounwind #0 {
+    %a = call noalias ptr @malloc(i64 noundef 16) #2
+    call void @f(ptr noundef nonnull align 8 %a) cold
+    %b = call i32 asm sideeffect "", "=r,r"(ptr %p) #2
+    ret void
+}
+
+attributes #0 = { noinline optnone uwtable "frame-pointer"="all" "no-builtins" }
+attributes #1 = { nounwind allocsize(0) allockind("alloc,uninitialized") memory(inaccessiblemem: readwrite) "alloc-family"="malloc" }
+attributes #2 = { alignstack=16 memory(read, argmem: readwrite) uwtable(sync) vscale_range(1,16) }
+"#;
+    let module = ModuleParser::new().parse(source).unwrap();
+    assert!(module.to_string() == source);
+
+    assert!(
+        module.get_attribute_group(0).unwrap().attributes
+            == [
+                FunctionAttribute::NoInline,
+                FunctionAttribute::OptimizeNone,
+                FunctionAttribute::UnwindTable { is_synchronous: false },
+                FunctionAttribute::String {
+                    key: "frame-pointer".to_string(),
+                    value: Some("all".to_string()),
+                },
+                FunctionAttribute::String {
+                    key: "no-builtins".to_string(),
+                    value: None,
+                },
+            ]
+    );
+    assert!(
+        module.get_attribute_group(2).unwrap().attributes[1]
+            == FunctionAttribute::Memory {
+                default: Some(MemoryAccess::Read),
+                locations: vec![(MemoryLocation::Argument, MemoryAccess::ReadWrite)],
+            }
+    );
+
+    let function = module.functions().next().unwrap();
+    assert!(module.function_attributes(&function.header.attributes, &function.header.attribute_groups).count() == 6);
+
+    let Operation::NoAssignment {
+        instruction: Instruction::Call {
+            function_arguments,
+            function_attributes,
+            attribute_groups,
+            ..
+        },
+        ..
+    } = &function.basic_blocks[0].operations[1]
+    else {
+        panic!("expected a call");
+    };
+    assert!(function_arguments[0].attributes == [ParameterAttribute::NoUndefined, ParameterAttribute::NonNull, ParameterAttribute::Alignment(8)]);
+    assert!(function_attributes == &[FunctionAttribute::Cold] && attribute_groups.is_empty());
+
+    // attribute groups can only be defined once
+    assert!(ModuleParser::new().parse("attributes #0 = { cold }\nattributes #0 = { hot }\n").is_err());
+
+    // attribute group numbers that don't fit in a `usize` are errors rather than panics
+    assert!(matches!(ModuleParser::new().parse("attributes #99999999999999999999 = { cold }\n"), Err(lalrpop_util::ParseError::User { .. })));
+    assert!(matches!(ModuleParser::new().parse("declare void @f() #99999999999999999999\n"), Err(lalrpop_util::ParseError::User { .. })));
+
+    // the colons in `memory(...)` and `captures(...)` can have spaces before them, even though they're usually lexed as part of field names
+    let attributes = "declare void @f(ptr captures(address, ret : full)) memory(argmem : read, errnomem: write)";
+    let declaration = FunctionDeclarationParser::new().parse(attributes).unwrap();
+    assert!(format!("declare {declaration}") == "declare void @f(ptr captures(address, ret: full)) memory(argmem: read, errnomem: write)");
+    assert!(FunctionDeclarationParser::new().parse("declare void @f() memory(line: read)").is_err());
+}
+
+/// makes sure parameter attributes with arguments are parsed into the right values, and are printed back out in their shortest form
+#[test]
+fn parameter_attributes() {
+    let source = "declare noundef range(i32 0, 10) i32 @f(ptr elementtype(i32) allocptr writeonly writable initializes((0, 4), (-8, -4)), float nofpclass(nan pinf sub), ptr captures(none), ptr captures(address, ret: full), ptr captures(ret: read_provenance), ptr captures(address_is_null))";
+    let declaration = FunctionDeclarationParser::new().parse(source).unwrap();
+    assert!(format!("declare {declaration}") == source);
+
+    assert!(
+        declaration.return_type_parameter_attributes[1]
+            == ParameterAttribute::Range {
+                range_type: Type::Integer { bit_width: 32 },
+                low_inclusive: Integer::zero(32),
+                high_exclusive: Integer::from_u64(32, 10),
+            }
+    );
+    assert!(
+        declaration.arguments[0].attributes
+            == [
+                ParameterAttribute::ElementType(Type::Integer { bit_width: 32 }),
+                ParameterAttribute::AllocatedPointer,
+                ParameterAttribute::WriteOnly,
+                ParameterAttribute::Writable,
+                ParameterAttribute::Initializes(vec![(0, 4), (-8, -4)]),
+            ]
+    );
+    assert!(declaration.arguments[1].attributes == [ParameterAttribute::NoFloatingPointClass(FloatingPointClasses::NAN | FloatingPointClasses::POSITIVE_INFINITY | FloatingPointClasses::SUBNORMAL)]);
+    assert!(
+        declaration.arguments[3].attributes
+            == [ParameterAttribute::Captures {
+                other: CaptureComponents::ADDRESS,
+                returned: CaptureComponents::FULL,
+            }]
+    );
+
+    // classes and components are written with the names that cover the most at once
+    let attribute = |source: &str| FunctionDeclarationParser::new().parse(&format!("declare void @f(ptr {source})")).unwrap().arguments[0].attributes[0].to_string();
+    assert!(attribute("nofpclass(snan qnan ninf pinf nzero pzero nsub psub nnorm pnorm)") == "nofpclass(all)");
+    assert!(attribute("nofpclass(3)") == "nofpclass(nan)");
+    assert!(attribute("captures(address_is_null, address, read_provenance, provenance)") == "captures(full)");
+    assert!(attribute("range(i8 -1, 254)") == "range(i8 -1, -2)");
+    // bounds that are equal once they're truncated to the type are an empty or full range
+    assert!(FunctionDeclarationParser::new().parse("declare void @f(i8 range(i8 -1, 255))").is_err());
+    assert!(FunctionDeclarationParser::new().parse("declare void @f(i8 range(i8 0, 0))").is_err());
+    assert!(FunctionDeclarationParser::new().parse("declare void @f(ptr range(ptr 0, 1))").is_err());
+    assert!(FunctionDeclarationParser::new().parse("declare void @f(ptr initializes((0, 9223372036854775808)))").is_err());
+}
+
+/// makes sure metadata nodes, named metadata and attachments are parsed into the right structure, and are printed back out the same way
+#[test]
+fn metadata() {
+    let source = r#"source_filename = "test.c"
+
+@x = global i32 0, align 4, !dbg !0
+
+declare !dbg !12 void @g(i32)
+
+define i32 @f(ptr %p, i32 %a) !dbg !5 {
+    call void @llvm.dbg.value(metadata i32 %a, metadata !9, metadata !DIExpression(DW_OP_constu, 42, DW_OP_stack_value)), !dbg !10
+    %1 = load i32, ptr %p, align 4, !tbaa !11, !range !{i32 0, i32 10}
+    ret i32 %1, !dbg !10
+}
+
+!llvm.dbg.cu = !{!2}
+!llvm.module.flags = !{!3, !4}
+
+!0 = !DIGlobalVariableExpression(var: !1, expr: !DIExpression())
+!1 = distinct !DIGlobalVariable(name: "x", scope: !2, file: !6, line: 1, type: !8, isLocal: false, isDefinition: true)
+!2 = distinct !DICompileUnit(language: DW_LANG_C11, file: !6, producer: "clang", isOptimized: false, runtimeVersion: 0, emissionKind: FullDebug, globals: !{!0}, nameTableKind: None)
+!3 = !{i32 7, !"Dwarf Version", i32 5}
+!4 = !{i32 2, !"Debug Info Version", i32 3}
+!5 = distinct !DISubprogram(name: "f", scope: !6, file: !6, line: 2, type: !7, flags: DIFlagPrototyped | DIFlagAllCallsDescribed, spFlags: DISPFlagDefinition, unit: !2, retainedNodes: !{})
+!6 = !DIFile(filename: "test.c", directory: "/tmp", checksumkind: CSK_MD5, checksum: "00ff")
+!7 = !DISubroutineType(types: !{null, !DIBasicType(name: "int", size: 32, encoding: DW_ATE_signed)})
+!8 = !DIBasicType(name: "int", size: 32, encoding: DW_ATE_signed)
+!9 = !DILocalVariable(name: "a", arg: 2, scope: !5, file: !6, line: 2, type: !8)
+!10 = !DILocation(line: 3, column: -1, scope: !5)
+!11 = !{!"int", !{!"omnipotent\0Achar", null}, i64 0}
+!12 = !DISubprogram(name: "g", scope: !6, file: !6, line: 1, spFlags: DISPFlagOptimized)
+!13 = !{!1, !DIExpression()}
+!14 = !{i32 1, !DIExpression(DW_OP_deref)}
+"#;
+    let module = ModuleParser::new().parse(source).unwrap();
+    assert!(module.to_string() == source);
+
+    assert!(module.named_metadata.len() == 2);
+    assert!(module.named_metadata[1].name == "!llvm.module.flags" && module.named_metadata[1].nodes == [3, 4]);
+    assert!(module.metadata_definitions.len() == 15);
+    assert!(module.get_metadata_node(1).unwrap().is_distinct && !module.get_metadata_node(0).unwrap().is_distinct);
+
+    let Metadata::Specialized(subprogram) = &module.get_metadata_node(5).unwrap().node else {
+        panic!("expected a specialized node");
+    };
+    assert!(subprogram.kind == "DISubprogram");
+    assert!(subprogram.fields[0] == (Some("name".to_string()), MetadataField::String("f".to_string())));
+    assert!(subprogram.fields[5] == (Some("flags".to_string()), MetadataField::Flags(vec!["DIFlagPrototyped".to_string(), "DIFlagAllCallsDescribed".to_string()])));
+    assert!(subprogram.fields[8] == (Some("retainedNodes".to_string()), MetadataField::Metadata(Some(Metadata::Tuple(vec![])))));
+    assert!(
+        module.get_metadata_node(11).unwrap().node
+            == Metadata::Tuple(vec![
+                Some(Metadata::String("int".to_string())),
+                Some(Metadata::Tuple(vec![Some(Metadata::String("omnipotent\nchar".to_string())), None])),
+                Some(Metadata::Value(Value::from_type_constant(Type::Integer { bit_width: 64 }, Constant::Integer(Integer::zero(64))).unwrap().into())),
+            ])
+    );
+
+    // specialized nodes after a comma are operands, not attachments
+    assert!(matches!(&module.get_metadata_node(13).unwrap().node, Metadata::Tuple(operands) if matches!(&operands[1], Some(Metadata::Specialized(node)) if node.kind == "DIExpression")));
+
+    let variable = module.global_variables().next().unwrap();
+    assert!(variable.alignment == Some(4) && variable.metadata[0].kind == "!dbg" && variable.metadata[0].node == Metadata::Node(0));
+    assert!(module.get_symbol("@g").unwrap().function_header().unwrap().metadata[0].node == Metadata::Node(12));
+
+    let function = module.functions().next().unwrap();
+    let block = &function.basic_blocks[0];
+    assert!(function.header.metadata[0].node == Metadata::Node(5));
+    assert!(block.terminator_metadata[0].node == Metadata::Node(10));
+
+    let Operation::NoAssignment {
+        instruction: Instruction::Call { function_arguments, .. },
+        metadata,
+    } = &block.operations[0]
+    else {
+        panic!("expected a call");
+    };
+    assert!(metadata[0].kind == "!dbg");
+    assert!(
+        *function_arguments[1].value
+            == Value::FromConstant {
+                constant_type: Type::Metadata,
+                constant: Constant::Metadata(Metadata::Node(9)),
+            }
+    );
+
+    let Operation::Assignment { metadata, .. } = &block.operations[1] else {
+        panic!("expected a load");
+    };
+    assert!(metadata.iter().map(|attachment| attachment.kind.as_str()).collect::<Vec<_>>() == ["!tbaa", "!range"]);
+
+    // hexadecimal fields are read as integers
+    let module = ModuleParser::new().parse("!0 = !DICompileUnit(dwoId: 0x1f)\n").unwrap();
+    assert!(module.metadata_definitions[0].node.to_string() == "!DICompileUnit(dwoId: 31)");
+
+    // named metadata is added to when it's defined again, but numbered nodes can only be defined once
+    let module = ModuleParser::new().parse("!a = !{!0}\n!a = !{!1}\n").unwrap();
+    assert!(module.named_metadata.len() == 1 && module.named_metadata[0].nodes == [0, 1]);
+    assert!(ModuleParser::new().parse("!0 = !{}\n!0 = !{}\n").is_err());
+
+    // node numbers that don't fit in a `usize` are errors rather than panics
+    assert!(matches!(ModuleParser::new().parse("!0 = !{!99999999999999999999999}\n"), Err(lalrpop_util::ParseError::User { .. })));
+}
+
+/// makes sure operand bundles on calls and invokes are parsed with their tags and inputs, and are printed back out the same way
+#[test]
+fn operand_bundles() {
+    let source = r#"define void @f(ptr %p) personality ptr @personality {
+    %1 = cleanuppad within none []
+    call void @g() [ "deopt"(i32 1, i64 2), "funclet"(token %1) ]
+    call void @llvm.assume(i1 true) [ "align"(ptr %p, i64 16) ]
+    %2 = call i32 @h(i32 1) #0 [ "gc-live"() ]
+    invoke void @g() [ "deopt"() ] to label %3 unwind label %4
+
+3:
+    ret void
+
+4:
+    unreachable
+}"#;
+    let function = FunctionParser::new().parse(source).unwrap();
+    assert!(function.to_string() == source);
+
+    let bundles = function.basic_blocks[0]
+        .operations
+        .iter()
+        .map(|operation| match operation {
+            Operation::Assignment {
+                value: Instruction::Call { operand_bundles, .. }, ..
+            }
+            | Operation::NoAssignment {
+                instruction: Instruction::Call { operand_bundles, .. },
+                ..
+            } => operand_bundles.clone(),
+            _ => vec![],
+        })
+        .collect::<Vec<_>>();
+    assert!(bundles[0].is_empty());
+    assert!(bundles[1].iter().map(|bundle| bundle.tag.as_str()).collect::<Vec<_>>() == ["deopt", "funclet"]);
+    assert!(bundles[1][0].inputs.len() == 2 && *bundles[1][1].inputs[0] == Value::FromIdentifier { value_type: Type::Token, identifier: "%1".to_string() });
+    assert!(bundles[2][0].tag == "align" && bundles[2][0].inputs.len() == 2);
+    assert!(bundles[3][0].tag == "gc-live" && bundles[3][0].inputs.is_empty());
+
+    let Terminator::Invoke { operand_bundles, .. } = &function.basic_blocks[0].terminator else {
+        panic!("expected an invoke");
+    };
+    assert!(operand_bundles[0].tag == "deopt");
+
+    // the bundle inputs are operands of the call, after its callee and arguments
+    let Operation::NoAssignment { instruction, .. } = &function.basic_blocks[0].operations[2] else {
+        panic!("expected a call");
+    };
+    assert!(instruction.operands().len() == 4);
+}
+
+/// makes sure calls can have any value as their callee, and that the function type is inferred from the arguments when only the return type is given
+#[test]
+fn indirect_calls() {
+    let source = r#"define void @f(ptr %fnptr) {
+    %1 = call i32 %fnptr(i32 1)
+    call void @g(i32 1)
+    call i64 (ptr, ...) @printf(ptr %fnptr)
+    call addrspace(1) void undef()
+    ret void
+}"#;
+    let function = FunctionParser::new().parse(source).unwrap();
+    assert!(function.to_string() == source);
+
+    let calls = function.basic_blocks[0]
+        .operations
+        .iter()
+        .map(|operation| match operation {
+            Operation::Assignment { value, .. } | Operation::NoAssignment { instruction: value, .. } => match value {
+                Instruction::Call { function_type, callee, .. } => (function_type.clone(), callee.clone()),
+                _ => panic!("expected a call"),
+            },
+        })
+        .collect::<Vec<_>>();
+
+    let i32 = Type::Integer { bit_width: 32 };
+    let ptr = Type::Pointer {
+        address_space: AddressSpace::Numbered(0),
+    };
+
+    // the short form infers the function type from the arguments
+    assert!(
+        calls[0].0
+            == Type::Function {
+                return_type: Box::new(i32.clone()),
+                parameters: vec![i32.clone()],
+                has_varargs: false
+            }
+    );
+    assert!(*calls[0].1 == Value::FromIdentifier { value_type: ptr.clone(), identifier: "%fnptr".to_string() });
+    assert!(*calls[1].1 == Value::FromIdentifier { value_type: ptr, identifier: "@g".to_string() });
+    assert!(matches!(calls[2].0, Type::Function { has_varargs: true, .. }));
+    assert!(
+        *calls[3].1
+            == Value::FromConstant {
+                constant_type: Type::Pointer {
+                    address_space: AddressSpace::Numbered(1)
+                },
+                constant: Constant::Undefined
+            }
+    );
+
+    // an explicit function type without varargs is printed in the short form
+    let explicit = "call void (i32) @g(i32 1)";
+    let function = FunctionParser::new().parse(&source.replace("call void @g(i32 1)", explicit)).unwrap();
+    assert!(function.to_string() == source);
+}
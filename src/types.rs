@@ -1,3 +1,5 @@
+use crate::integer::Integer;
+
 /// a specific kind of floating point type
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum FloatingPointKind {
@@ -17,6 +19,19 @@ pub enum FloatingPointKind {
     PpcFp128,
 }
 
+impl FloatingPointKind {
+    /// how many bits wide values of this kind are
+    pub fn bit_width(self) -> usize {
+        match self {
+            Self::Binary16 | Self::Brain => 16,
+            Self::Binary32 => 32,
+            Self::Binary64 => 64,
+            Self::X86Fp80 => 80,
+            Self::Binary128 | Self::PpcFp128 => 128,
+        }
+    }
+}
+
 /// an address space that a pointer can point to
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum AddressSpace {
@@ -117,6 +132,13 @@ pub enum Type {
     /// an opaque structure type, which doesn't have its contents defined yet.
     /// this type is neither first-class nor sized
     OpaqueStructure,
+    /// a reference to a structure type defined elsewhere with a name, like `%struct.inode`.
+    /// identified structure types are only ever equal to other identified types with the same name, even if a literal structure type has the same contents.
+    /// since the definition is stored separately (usually in a module), this type is neither first-class nor sized until it's resolved
+    Identified {
+        /// the name of this type, including its `%` prefix
+        name: String,
+    },
 }
 
 impl Type {
@@ -135,10 +157,72 @@ impl Type {
             Self::Integer { .. } | Self::FloatingPoint { .. } | Self::Pointer { .. } | Self::Vector { .. } | Self::Array { .. } | Self::Structure { .. }
         )
     }
+
+    /// the type of the elements of this type if it's a vector, otherwise this type itself
+    pub fn scalar_type(&self) -> &Type {
+        match self {
+            Self::Vector { element_type, .. } => element_type,
+            _ => self,
+        }
+    }
+
+    /// the length of this type and whether it's scalable if it's a vector
+    pub fn vector_shape(&self) -> Option<(usize, bool)> {
+        match self {
+            Self::Vector { length, is_scalable, .. } => Some((*length, *is_scalable)),
+            _ => None,
+        }
+    }
+
+    /// the bit width of this type if it's an integer or vector of integers
+    pub fn integer_bit_width(&self) -> Option<usize> {
+        match self.scalar_type() {
+            Self::Integer { bit_width } => Some(*bit_width),
+            _ => None,
+        }
+    }
+
+    /// the kind of floating point type this is if it's a floating point type or vector of floating point types
+    pub fn floating_point_kind(&self) -> Option<FloatingPointKind> {
+        match self.scalar_type() {
+            Self::FloatingPoint { kind } => Some(*kind),
+            _ => None,
+        }
+    }
+}
+
+/// something the definitions of identified types can be looked up in, usually a module
+pub trait TypeDefinitions {
+    /// looks up the definition of an identified type by its name (including its `%` prefix)
+    fn get_type_definition(&self, name: &str) -> Option<&Type>;
+
+    /// follows identified types until a type that isn't identified is found, or returns `None` if an identified type isn't defined
+    fn resolve_type<'a>(&'a self, mut t: &'a Type) -> Option<&'a Type> {
+        // types can be defined as themselves, so this has to keep track of where it's been to avoid looping forever
+        let mut visited = Vec::new();
+
+        while let Type::Identified { name } = t {
+            if visited.contains(&name) {
+                return None;
+            }
+
+            visited.push(name);
+            t = self.get_type_definition(name)?;
+        }
+
+        Some(t)
+    }
+}
+
+/// for when there aren't any identified types to look up
+impl TypeDefinitions for () {
+    fn get_type_definition(&self, _name: &str) -> Option<&Type> {
+        None
+    }
 }
 
 /// LLVM parameter attributes (https://llvm.org/docs/LangRef.html#paramattrs)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ParameterAttribute {
     /// indicates that the parameter or return value should be zero-extended as required.
     /// corresponds to LLVM's `zeroext` parameter attribute
@@ -164,7 +248,9 @@ pub enum ParameterAttribute {
     /// specifies that this parameter is a pointer to a structure that is the return value of this function.
     /// corresponds to LLVM's `sret` parameter attribute
     ReturnStructure(Type),
-    // TODO: elementtype
+    /// specifies the type of the value this pointer points to, for intrinsics and inline assembly that need to know it.
+    /// corresponds to LLVM's `elementtype` parameter attribute
+    ElementType(Type),
     /// specifies the alignment of the parameter if it's a pointer or vector of pointers.
     /// corresponds to LLVM's `align` parameter attribute
     Alignment(usize),
@@ -202,7 +288,9 @@ pub enum ParameterAttribute {
     /// specifies that this parameter shouldn't contain undefined or poison bits.
     /// corresponds to LLVM's `noundef` parameter attribute
     NoUndefined,
-    // TODO: nofpclass
+    /// specifies that this floating point parameter or return value is never in any of the given classes.
+    /// corresponds to LLVM's `nofpclass` parameter attribute
+    NoFloatingPointClass(FloatingPointClasses),
     /// specifies the preferred alignment for if this parameter is allocated stack space.
     /// corresponds to LLVM's `alignstack` parameter attribute
     StackAlignment(usize),
@@ -210,14 +298,27 @@ pub enum ParameterAttribute {
     /// where this function can return either a pointer aligned to at least the value of this argument or a null pointer.
     /// corresponds to LLVM's `allocalign` parameter attribute
     AllocationAlignment,
-    // TODO: allocptr (what does it mean? does the pointer have to be invalidated, or does it just mean it'll be fucked with? how would this help with optimization at all?)
+    /// specifies that this parameter is the pointer to the allocation that an allocator function like `realloc` or `free` works on.
+    /// corresponds to LLVM's `allocptr` parameter attribute
+    AllocatedPointer,
     /// specifies that this parameter will not be directly dereferenced (though the memory it points to could still be modified).
     /// corresponds to LLVM's `readnone` parameter attribute
     NoDereference,
     /// specifies that this parameter will not be directly dereferenced for write operations, though the memory it points to could still be modified by other pointers.
     /// corresponds to LLVM's `readonly` parameter attribute
     ReadOnly,
-    // TODO: writeonly, writable
+    /// specifies that this parameter will only be dereferenced for write operations.
+    /// corresponds to LLVM's `writeonly` parameter attribute
+    WriteOnly,
+    /// specifies that the memory this parameter points to (as much of it as is dereferenceable) can be written to without trapping, and that writes can be added speculatively.
+    /// corresponds to LLVM's `writable` parameter attribute
+    Writable,
+    /// specifies that the function writes to the given ranges of bytes relative to this pointer before reading them, as half-open `(start, end)` ranges.
+    /// corresponds to LLVM's `initializes` parameter attribute
+    Initializes(Vec<(i64, i64)>),
+    /// specifies which parts of this pointer the function can capture. `returned` is what can be captured by returning the pointer, and `other` is everything else.
+    /// corresponds to LLVM's `captures` parameter attribute
+    Captures { other: CaptureComponents, returned: CaptureComponents },
     /// specifies that this parameter will be poisoned (or the value pointed to by this parameter) if the function call unwinds.
     /// corresponds to LLVM's `dead_on_unwind` parameter attribute
     PoisonOnUnwind,
@@ -225,7 +326,225 @@ pub enum ParameterAttribute {
     /// corresponds to LLVM's `range` parameter attribute
     Range {
         range_type: Type,
-        low_inclusive: Vec<usize>,
-        high_exclusive: Vec<usize>,
+        /// the smallest value this parameter can have, which has the bit width of `range_type`
+        low_inclusive: Integer,
+        /// one more than the largest value this parameter can have. the range wraps around if this is less than `low_inclusive`
+        high_exclusive: Integer,
     },
 }
+
+/// a set of classes of floating point values, used by the `nofpclass` attribute. each class is a bit, in the same order as in LLVM
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct FloatingPointClasses(pub u16);
+
+impl FloatingPointClasses {
+    pub const SIGNALING_NAN: Self = Self(1 << 0);
+    pub const QUIET_NAN: Self = Self(1 << 1);
+    pub const NEGATIVE_INFINITY: Self = Self(1 << 2);
+    pub const NEGATIVE_NORMAL: Self = Self(1 << 3);
+    pub const NEGATIVE_SUBNORMAL: Self = Self(1 << 4);
+    pub const NEGATIVE_ZERO: Self = Self(1 << 5);
+    pub const POSITIVE_ZERO: Self = Self(1 << 6);
+    pub const POSITIVE_SUBNORMAL: Self = Self(1 << 7);
+    pub const POSITIVE_NORMAL: Self = Self(1 << 8);
+    pub const POSITIVE_INFINITY: Self = Self(1 << 9);
+    pub const NAN: Self = Self(Self::SIGNALING_NAN.0 | Self::QUIET_NAN.0);
+    pub const INFINITY: Self = Self(Self::NEGATIVE_INFINITY.0 | Self::POSITIVE_INFINITY.0);
+    pub const NORMAL: Self = Self(Self::NEGATIVE_NORMAL.0 | Self::POSITIVE_NORMAL.0);
+    pub const SUBNORMAL: Self = Self(Self::NEGATIVE_SUBNORMAL.0 | Self::POSITIVE_SUBNORMAL.0);
+    pub const ZERO: Self = Self(Self::NEGATIVE_ZERO.0 | Self::POSITIVE_ZERO.0);
+    pub const ALL: Self = Self((1 << 10) - 1);
+
+    /// whether every class in `other` is also in this set
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl std::ops::BitOr for FloatingPointClasses {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// the parts of a pointer that can be captured, used by the `captures` attribute. each part is a bit, with the same values as in LLVM
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct CaptureComponents(pub u8);
+
+impl CaptureComponents {
+    pub const NONE: Self = Self(0);
+    /// only whether the pointer is null can be captured
+    pub const ADDRESS_IS_NULL: Self = Self(1 << 0);
+    /// the whole address can be captured, which includes whether it's null
+    pub const ADDRESS: Self = Self(Self::ADDRESS_IS_NULL.0 | 1 << 1);
+    /// the pointer can be captured in a way that only allows reading through it
+    pub const READ_PROVENANCE: Self = Self(1 << 2);
+    /// the pointer can be captured in a way that allows reading and writing through it
+    pub const PROVENANCE: Self = Self(Self::READ_PROVENANCE.0 | 1 << 3);
+    pub const FULL: Self = Self(Self::ADDRESS.0 | Self::PROVENANCE.0);
+
+    /// whether every component in `other` is also in this set
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl std::ops::BitOr for CaptureComponents {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// a kind of memory that a function can access, used by the `memory` function attribute
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MemoryLocation {
+    /// memory pointed to by the function's pointer arguments. corresponds to LLVM's `argmem`
+    Argument,
+    /// memory that isn't accessible by the module, like the internal state of a memory allocator. corresponds to LLVM's `inaccessiblemem`
+    Inaccessible,
+    /// the `errno` variable. corresponds to LLVM's `errnomem`
+    ErrorNumber,
+}
+
+/// how a function can access some kind of memory
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MemoryAccess {
+    None,
+    Read,
+    Write,
+    ReadWrite,
+}
+
+/// LLVM function attributes (https://llvm.org/docs/LangRef.html#function-attributes)
+#[derive(Debug, Clone, PartialEq)]
+pub enum FunctionAttribute {
+    /// specifies what kinds of allocation or deallocation this function does, like `"alloc,zeroed"`.
+    /// corresponds to LLVM's `allockind` function attribute
+    AllocationKind(String),
+    /// indicates that this function is an allocator that returns a pointer to an allocation as big as the argument at index `element_size`,
+    /// times the argument at index `count` if there is one.
+    /// corresponds to LLVM's `allocsize` function attribute
+    AllocationSize { element_size: usize, count: Option<usize> },
+    /// corresponds to LLVM's `alwaysinline` function attribute
+    AlwaysInline,
+    /// indicates that a call to this function should be treated as a call to a builtin, even if the callee was declared with `nobuiltin`.
+    /// corresponds to LLVM's `builtin` function attribute
+    Builtin,
+    /// indicates that this function is rarely called.
+    /// corresponds to LLVM's `cold` function attribute
+    Cold,
+    /// indicates that calls to this function can't be made control dependent on any more values.
+    /// corresponds to LLVM's `convergent` function attribute
+    Convergent,
+    /// corresponds to LLVM's `disable_sanitizer_instrumentation` function attribute
+    DisableSanitizerInstrumentation,
+    /// indicates that this function is called often.
+    /// corresponds to LLVM's `hot` function attribute
+    Hot,
+    /// corresponds to LLVM's `inlinehint` function attribute
+    InlineHint,
+    /// corresponds to LLVM's `jumptable` function attribute
+    JumpTable,
+    /// specifies what kinds of memory this function can access. `default` applies to every location that isn't listed.
+    /// corresponds to LLVM's `memory` function attribute
+    Memory { default: Option<MemoryAccess>, locations: Vec<(MemoryLocation, MemoryAccess)> },
+    /// corresponds to LLVM's `minsize` function attribute
+    MinimumSize,
+    /// indicates that this function must either return, unwind or interact with the environment in some way within a finite amount of time.
+    /// corresponds to LLVM's `mustprogress` function attribute
+    MustProgress,
+    /// corresponds to LLVM's `naked` function attribute
+    Naked,
+    /// corresponds to LLVM's `nobuiltin` function attribute
+    NoBuiltin,
+    /// indicates that this function never calls back into the module that called it.
+    /// corresponds to LLVM's `nocallback` function attribute
+    NoCallback,
+    /// corresponds to LLVM's `nocf_check` function attribute
+    NoControlFlowCheck,
+    /// corresponds to LLVM's `noduplicate` function attribute
+    NoDuplicate,
+    /// indicates that this function doesn't free any memory that was allocated before it was called.
+    /// corresponds to LLVM's `nofree` function attribute
+    NoFree,
+    /// corresponds to LLVM's `noimplicitfloat` function attribute
+    NoImplicitFloat,
+    /// corresponds to LLVM's `noinline` function attribute
+    NoInline,
+    /// corresponds to LLVM's `nomerge` function attribute
+    NoMerge,
+    /// corresponds to LLVM's `nonlazybind` function attribute
+    NonLazyBind,
+    /// corresponds to LLVM's `noprofile` function attribute
+    NoProfile,
+    /// indicates that this function never calls itself, directly or indirectly.
+    /// corresponds to LLVM's `norecurse` function attribute
+    NoRecurse,
+    /// corresponds to LLVM's `noredzone` function attribute
+    NoRedZone,
+    /// indicates that this function never returns normally.
+    /// corresponds to LLVM's `noreturn` function attribute
+    NoReturn,
+    /// indicates that this function doesn't synchronize with any other threads.
+    /// corresponds to LLVM's `nosync` function attribute
+    NoSynchronize,
+    /// indicates that this function never unwinds.
+    /// corresponds to LLVM's `nounwind` function attribute
+    NoUnwind,
+    /// indicates that null pointers are valid to dereference in this function.
+    /// corresponds to LLVM's `null_pointer_is_valid` function attribute
+    NullPointerIsValid,
+    /// corresponds to LLVM's `optnone` function attribute
+    OptimizeNone,
+    /// corresponds to LLVM's `optsize` function attribute
+    OptimizeForSize,
+    /// corresponds to LLVM's `presplitcoroutine` function attribute
+    PreSplitCoroutine,
+    /// indicates that this function can return more than once, like `setjmp`.
+    /// corresponds to LLVM's `returns_twice` function attribute
+    ReturnsTwice,
+    /// corresponds to LLVM's `safestack` function attribute
+    SafeStack,
+    /// corresponds to LLVM's `sanitize_address` function attribute
+    SanitizeAddress,
+    /// corresponds to LLVM's `sanitize_hwaddress` function attribute
+    SanitizeHardwareAddress,
+    /// corresponds to LLVM's `sanitize_memory` function attribute
+    SanitizeMemory,
+    /// corresponds to LLVM's `sanitize_thread` function attribute
+    SanitizeThread,
+    /// corresponds to LLVM's `shadowcallstack` function attribute
+    ShadowCallStack,
+    /// indicates that this function has no side effects and can be called speculatively, even where it wouldn't have been called otherwise.
+    /// corresponds to LLVM's `speculatable` function attribute
+    Speculatable,
+    /// corresponds to LLVM's `speculative_load_hardening` function attribute
+    SpeculativeLoadHardening,
+    /// specifies the alignment the stack should have when this function is entered.
+    /// corresponds to LLVM's `alignstack` function attribute
+    StackAlignment(usize),
+    /// corresponds to LLVM's `ssp` function attribute
+    StackProtect,
+    /// corresponds to LLVM's `sspreq` function attribute
+    StackProtectRequired,
+    /// corresponds to LLVM's `sspstrong` function attribute
+    StackProtectStrong,
+    /// indicates that this function relies on floating point exceptions and rounding modes, so floating point operations can't be changed.
+    /// corresponds to LLVM's `strictfp` function attribute
+    StrictFloatingPoint,
+    /// specifies that this function needs an unwind table, which only has to be correct at call sites if it's synchronous.
+    /// corresponds to LLVM's `uwtable` function attribute
+    UnwindTable { is_synchronous: bool },
+    /// specifies the smallest and largest values `vscale` can have in this function.
+    /// corresponds to LLVM's `vscale_range` function attribute
+    VScaleRange { minimum: usize, maximum: Option<usize> },
+    /// indicates that this function always returns or unwinds eventually.
+    /// corresponds to LLVM's `willreturn` function attribute
+    WillReturn,
+    /// an attribute that LLVM doesn't know about itself, like `"frame-pointer"="all"`, which is usually read by the code generator
+    String { key: String, value: Option<String> },
+}
@@ -0,0 +1,1137 @@
+use crate::{
+    integer::Integer,
+    ir::{AtomicOperation, CallArgument, Constant, IncomingValue, Instruction, LandingPadClause, Metadata, MetadataField, OperandBundle, Ordering, Terminator, Value},
+    llvm::{Function, LinkageType, MetadataAttachment, Module, Operation, Symbol},
+    types::{FloatingPointKind, Type, TypeDefinitions},
+};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt,
+    sync::Arc,
+};
+
+#[cfg(test)]
+pub mod test;
+
+/// where a verifier error was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Location {
+    /// a function or global variable as a whole
+    Global { name: String },
+    /// an instruction in a basic block. the terminator of a block comes after every other instruction in it
+    Instruction { function: String, block: String, index: usize },
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Global { name } => write!(f, "{name}"),
+            Self::Instruction { function, block, index } => write!(f, "{function}, block {block}, instruction {index}"),
+        }
+    }
+}
+
+/// a rule of the IR that something doesn't follow
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifierError {
+    pub location: Location,
+    pub message: String,
+}
+
+impl fmt::Display for VerifierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// checks every function and global variable in a module, returning every error that was found
+pub fn verify_module(module: &Module) -> Result<(), Vec<VerifierError>> {
+    let mut errors = Vec::new();
+
+    for symbol in &module.symbols {
+        match symbol {
+            Symbol::Function(function) => errors.extend(FunctionVerifier::new(function, module, Some(module)).verify()),
+            Symbol::FunctionDeclaration(header) => {
+                let mut verifier = ValueVerifier::new(module, Location::Global { name: header.name.clone() });
+                verifier.check_attribute_groups(&header.attribute_groups);
+                verifier.check_metadata_attachments(&header.metadata);
+                errors.extend(verifier.errors);
+            }
+            Symbol::GlobalVariable(variable) => {
+                let mut verifier = ValueVerifier::new(module, Location::Global { name: variable.name.clone() });
+
+                match &variable.initializer {
+                    Some(initializer) => {
+                        verifier.check_value(initializer);
+                        verifier.expect_type(initializer, &variable.value_type, "initializer");
+                    }
+                    // globals are only allowed to be missing an initializer when they're defined in another module
+                    None if !matches!(variable.linkage, LinkageType::External | LinkageType::ExternalWeak) => {
+                        verifier.error(format!("global variables without an initializer must have external or extern_weak linkage, not {}", variable.linkage));
+                    }
+                    None => (),
+                }
+
+                verifier.check_metadata_attachments(&variable.metadata);
+                errors.extend(verifier.errors);
+            }
+        }
+    }
+
+    for metadata in &module.named_metadata {
+        let mut verifier = ValueVerifier::new(module, Location::Global { name: metadata.name.clone() });
+
+        for id in &metadata.nodes {
+            verifier.check_metadata(&Metadata::Node(*id));
+        }
+
+        errors.extend(verifier.errors);
+    }
+
+    for definition in &module.metadata_definitions {
+        let mut verifier = ValueVerifier::new(module, Location::Global { name: format!("!{}", definition.id) });
+        verifier.check_metadata(&definition.node);
+        errors.extend(verifier.errors);
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// checks a single function on its own, returning every error that was found.
+/// since there's no module here, references to globals can't be checked and identified types are looked up in `definitions`
+pub fn verify_function(function: &Function, definitions: &dyn TypeDefinitions) -> Result<(), Vec<VerifierError>> {
+    let errors = FunctionVerifier::new(function, definitions, None).verify();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// checks values against the types they're used as and the values they refer to
+struct ValueVerifier<'a> {
+    definitions: &'a dyn TypeDefinitions,
+    module: Option<&'a Module>,
+    /// the types of every local value (including block labels) that's been defined, by name
+    values: HashMap<String, Type>,
+    /// where errors will be reported
+    location: Location,
+    errors: Vec<VerifierError>,
+}
+
+impl<'a> ValueVerifier<'a> {
+    /// a verifier for something outside of a function, which can see everything in `module`
+    fn new(module: &'a Module, location: Location) -> Self {
+        Self {
+            definitions: module,
+            module: Some(module),
+            values: HashMap::new(),
+            location,
+            errors: Vec::new(),
+        }
+    }
+
+    fn error(&mut self, message: String) {
+        self.errors.push(VerifierError {
+            location: self.location.clone(),
+            message,
+        });
+    }
+
+    fn type_of(&mut self, value: &Value) -> Option<Type> {
+        let t = value.get_type(self.definitions);
+
+        if t.is_none() {
+            self.error(format!("couldn't determine the type of {value}"));
+        }
+
+        t
+    }
+
+    /// makes sure a value has the given type, describing the value as `what` if it doesn't
+    fn expect_type(&mut self, value: &Value, expected: &Type, what: &str) {
+        if let Some(t) = self.type_of(value) {
+            if &t != expected {
+                self.error(format!("{what} has type {t}, but {expected} is required"));
+            }
+        }
+    }
+
+    /// makes sure every attribute group that's referenced is defined, which can only be checked when there's a module
+    fn check_attribute_groups(&mut self, attribute_groups: &[usize]) {
+        if let Some(module) = self.module {
+            for id in attribute_groups {
+                if module.get_attribute_group(*id).is_none() {
+                    self.error(format!("use of undefined attribute group #{id}"));
+                }
+            }
+        }
+    }
+
+    /// makes sure every numbered node that's referenced is defined, which can only be checked when there's a module, and checks the values used as metadata
+    fn check_metadata(&mut self, metadata: &Metadata) {
+        match metadata {
+            Metadata::Node(id) => {
+                if let Some(module) = self.module {
+                    if module.get_metadata_node(*id).is_none() {
+                        self.error(format!("use of undefined metadata !{id}"));
+                    }
+                }
+            }
+            Metadata::String(_) => (),
+            Metadata::Value(value) => self.check_value(value),
+            Metadata::Tuple(operands) => {
+                for operand in operands.iter().flatten() {
+                    self.check_metadata(operand);
+                }
+            }
+            Metadata::Specialized(node) => {
+                for (_, field) in &node.fields {
+                    if let MetadataField::Metadata(Some(metadata)) = field {
+                        self.check_metadata(metadata);
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_metadata_attachments(&mut self, metadata: &[MetadataAttachment]) {
+        for attachment in metadata {
+            self.check_metadata(&attachment.node);
+        }
+    }
+
+    /// makes sure identifiers refer to something that exists with the same type, and that constants fit their types
+    fn check_value(&mut self, value: &Value) {
+        match value {
+            Value::FromIdentifier { value_type, identifier } if identifier.starts_with('%') => match self.values.get(identifier) {
+                Some(t) if t != value_type => {
+                    let t = t.clone();
+                    self.error(format!("{identifier} is defined with type {t} but used as {value_type}"));
+                }
+                Some(_) => (),
+                None => self.error(format!("use of undefined value {identifier}")),
+            },
+            Value::FromIdentifier { value_type, identifier } => {
+                if !matches!(value_type, Type::Pointer { .. }) {
+                    self.error(format!("global {identifier} used as non-pointer type {value_type}"));
+                }
+
+                if let Some(module) = self.module {
+                    match module.get_symbol(identifier) {
+                        // pointers to globals are written with the address space they're used in, like for the callee of `call addrspace(1)`, which has to be where the global is
+                        Some(symbol) if matches!(value_type, Type::Pointer { address_space } if *address_space != symbol.address_space()) => {
+                            let t = Type::Pointer { address_space: symbol.address_space() };
+                            self.error(format!("global {identifier} is defined with type {t} but used as {value_type}"));
+                        }
+                        Some(_) => (),
+                        None => self.error(format!("use of undefined global {identifier}")),
+                    }
+                }
+            }
+            Value::FromConstant { constant_type, constant } => {
+                match self.definitions.resolve_type(constant_type) {
+                    // the types of constant expressions are checked below, since they can depend on identified types
+                    Some(t) if !matches!(constant, Constant::Expression(_)) && !constant.is_compatible_with_type(t) => self.error(format!("constant {value} is incompatible with its type")),
+                    Some(_) => (),
+                    None => self.error(format!("use of undefined type {constant_type}")),
+                }
+
+                match constant {
+                    Constant::Structure(values) | Constant::Array(values) | Constant::Vector(values) => {
+                        for value in values {
+                            self.check_value(value);
+                        }
+                    }
+                    Constant::Metadata(metadata) => self.check_metadata(metadata),
+                    Constant::Expression(instruction) => {
+                        for operand in instruction.operands() {
+                            if matches!(operand.as_ref(), Value::FromIdentifier { identifier, .. } if identifier.starts_with('%')) {
+                                self.error(format!("constant expressions can only use constants and globals, not {operand}"));
+                            } else {
+                                self.check_value(operand);
+                            }
+                        }
+
+                        self.check_instruction(instruction);
+                        self.check_constant_division(value, instruction);
+
+                        // an undefined type is already reported above
+                        if let (Some(t), Some(expected)) = (instruction.get_result_type(self.definitions), self.definitions.resolve_type(constant_type)) {
+                            if self.definitions.resolve_type(&t) != Some(expected) {
+                                self.error(format!("constant expression {value} has type {t}, but {constant_type} is required"));
+                            }
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            Value::FromInstruction { instruction } => {
+                for operand in instruction.operands() {
+                    self.check_value(operand);
+                }
+            }
+            Value::FromLabel => (),
+        }
+    }
+
+    /// makes sure a constant expression doesn't divide by zero or overflow a signed division, since unlike other constant expressions that wrap these are undefined behavior
+    fn check_constant_division(&mut self, value: &Value, instruction: &Instruction) {
+        let (left_hand_side, right_hand_side, is_signed) = match instruction {
+            Instruction::UnsignedDivide { left_hand_side, right_hand_side, .. } | Instruction::UnsignedRemainder { left_hand_side, right_hand_side } => (left_hand_side, right_hand_side, false),
+            Instruction::SignedDivide { left_hand_side, right_hand_side, .. } | Instruction::SignedRemainder { left_hand_side, right_hand_side } => (left_hand_side, right_hand_side, true),
+            _ => return,
+        };
+
+        match (left_hand_side.evaluate(self.definitions), right_hand_side.evaluate(self.definitions)) {
+            (_, Some(Constant::Integer(divisor))) if divisor.is_zero() => self.error(format!("constant expression {value} divides by zero")),
+            (_, Some(Constant::Zero | Constant::Boolean(false))) => self.error(format!("constant expression {value} divides by zero")),
+            (Some(Constant::Integer(dividend)), Some(Constant::Integer(divisor))) if is_signed && dividend == Integer::signed_minimum(dividend.bit_width()) && divisor == Integer::all_ones(divisor.bit_width()) => {
+                self.error(format!("constant expression {value} overflows"));
+            }
+            _ => (),
+        }
+    }
+
+    fn check_integer_operands(&mut self, left_hand_side: &Value, right_hand_side: &Value) {
+        let (Some(left), Some(right)) = (self.type_of(left_hand_side), self.type_of(right_hand_side)) else {
+            return;
+        };
+
+        if left != right {
+            self.error(format!("operand types {left} and {right} don't match"));
+        } else if left.integer_bit_width().is_none() {
+            self.error(format!("operands must be integers or vectors of integers, not {left}"));
+        }
+    }
+
+    fn check_float_operands(&mut self, left_hand_side: &Value, right_hand_side: &Value) {
+        let (Some(left), Some(right)) = (self.type_of(left_hand_side), self.type_of(right_hand_side)) else {
+            return;
+        };
+
+        if left != right {
+            self.error(format!("operand types {left} and {right} don't match"));
+        } else if left.floating_point_kind().is_none() {
+            self.error(format!("operands must be floating point values or vectors of floating point values, not {left}"));
+        }
+    }
+
+    fn check_pointer(&mut self, pointer: &Value) {
+        if let Some(t) = self.type_of(pointer) {
+            if !matches!(t, Type::Pointer { .. }) {
+                self.error(format!("expected a pointer, got {t}"));
+            }
+        }
+    }
+
+    /// makes sure an index into a vector is an integer
+    fn check_vector_index(&mut self, index: &Value) {
+        if let Some(t) = self.type_of(index) {
+            if !matches!(t, Type::Integer { .. }) {
+                self.error(format!("vector indices must be integers, not {t}"));
+            }
+        }
+    }
+
+    /// makes sure a shufflevector mask is a constant vector of `i32`s that only picks elements out of two vectors with the given length and scalability
+    fn check_shuffle_mask(&mut self, mask: &Value, (length, is_scalable): (usize, bool)) {
+        let Value::FromConstant { constant_type, constant } = mask else {
+            self.error(format!("shufflevector mask must be a constant, not {mask}"));
+            return;
+        };
+
+        let Some((_, is_mask_scalable)) = constant_type.vector_shape().filter(|_| constant_type.integer_bit_width() == Some(32)) else {
+            self.error(format!("shufflevector mask must be a vector of i32, not {constant_type}"));
+            return;
+        };
+
+        if is_mask_scalable != is_scalable {
+            self.error(format!("shufflevector mask {mask} must be scalable if and only if the vectors are"));
+        }
+
+        match constant {
+            Constant::Vector(_) if is_mask_scalable => self.error("masks for scalable vectors can only be zeroinitializer, undef or poison".to_string()),
+            Constant::Vector(values) => {
+                for value in values {
+                    match value.as_ref() {
+                        Value::FromConstant {
+                            constant: Constant::Integer(index), ..
+                        } if index.to_u64().is_some_and(|index| index < length as u64 * 2) => (),
+                        Value::FromConstant {
+                            constant: Constant::Undefined | Constant::Poison,
+                            ..
+                        } => (),
+                        value => self.error(format!("shufflevector mask elements must be constants below {}, not {value}", length * 2)),
+                    }
+                }
+            }
+            // anything else either doesn't depend on the vectors or is incompatible with the mask's type, which is already reported
+            _ => (),
+        }
+    }
+
+    /// checks that an integer cast changes the width of its operand in the right direction, with `should_widen` specifying which way
+    fn check_integer_resize(&mut self, value: &Value, new_type: &Type, should_widen: bool, name: &str) {
+        self.check_resize(value, new_type, should_widen, name, Type::integer_bit_width, "integers");
+    }
+
+    /// checks that a floating point cast changes the width of its operand in the right direction, with `should_widen` specifying which way
+    fn check_float_resize(&mut self, value: &Value, new_type: &Type, should_widen: bool, name: &str) {
+        self.check_resize(value, new_type, should_widen, name, |t| t.floating_point_kind().map(FloatingPointKind::bit_width), "floating point values");
+    }
+
+    /// checks a cast between two types that `bit_width` gives the scalar width of, where `kind` describes what those types are
+    fn check_resize(&mut self, value: &Value, new_type: &Type, should_widen: bool, name: &str, bit_width: fn(&Type) -> Option<usize>, kind: &str) {
+        let Some(old_type) = self.type_of(value) else {
+            return;
+        };
+
+        let (Some(old_width), Some(new_width)) = (bit_width(&old_type), bit_width(new_type)) else {
+            self.error(format!("{name} can only convert between {kind} or vectors of {kind}, not {old_type} and {new_type}"));
+            return;
+        };
+
+        if old_type.vector_shape() != new_type.vector_shape() {
+            self.error(format!("{name} can't change the shape of {old_type} to {new_type}"));
+        } else if should_widen && new_width <= old_width {
+            self.error(format!("{name} must make its operand wider, but {old_type} is at least as wide as {new_type}"));
+        } else if !should_widen && new_width >= old_width {
+            self.error(format!("{name} must make its operand narrower, but {old_type} is at most as wide as {new_type}"));
+        }
+    }
+
+    /// checks a conversion between different kinds of types where both sides have to match the given predicates and have the same shape
+    fn check_conversion(&mut self, value: &Value, new_type: &Type, from: fn(&Type) -> bool, to: fn(&Type) -> bool, name: &str) {
+        let Some(old_type) = self.type_of(value) else {
+            return;
+        };
+
+        if !from(old_type.scalar_type()) || !to(new_type.scalar_type()) || old_type.vector_shape() != new_type.vector_shape() {
+            self.error(format!("{name} can't convert {old_type} to {new_type}"));
+        }
+    }
+
+    fn check_instruction(&mut self, instruction: &Instruction) {
+        let is_pointer = |t: &Type| matches!(t, Type::Pointer { .. });
+        let is_integer = |t: &Type| matches!(t, Type::Integer { .. });
+        let is_float = |t: &Type| matches!(t, Type::FloatingPoint { .. });
+
+        match instruction {
+            Instruction::Add { left_hand_side, right_hand_side, .. }
+            | Instruction::Subtract { left_hand_side, right_hand_side, .. }
+            | Instruction::Multiply { left_hand_side, right_hand_side, .. }
+            | Instruction::UnsignedDivide { left_hand_side, right_hand_side, .. }
+            | Instruction::SignedDivide { left_hand_side, right_hand_side, .. }
+            | Instruction::UnsignedRemainder { left_hand_side, right_hand_side }
+            | Instruction::SignedRemainder { left_hand_side, right_hand_side }
+            | Instruction::ShiftLeft { left_hand_side, right_hand_side, .. }
+            | Instruction::LogicalShiftRight { left_hand_side, right_hand_side, .. }
+            | Instruction::ArithmeticShiftRight { left_hand_side, right_hand_side, .. }
+            | Instruction::And { left_hand_side, right_hand_side }
+            | Instruction::Or { left_hand_side, right_hand_side, .. }
+            | Instruction::ExclusiveOr { left_hand_side, right_hand_side } => self.check_integer_operands(left_hand_side, right_hand_side),
+            Instruction::FloatAdd { left_hand_side, right_hand_side, .. }
+            | Instruction::FloatSubtract { left_hand_side, right_hand_side, .. }
+            | Instruction::FloatMultiply { left_hand_side, right_hand_side, .. }
+            | Instruction::FloatDivide { left_hand_side, right_hand_side, .. }
+            | Instruction::FloatRemainder { left_hand_side, right_hand_side, .. } => self.check_float_operands(left_hand_side, right_hand_side),
+            Instruction::FloatNegate { value, .. } => {
+                if let Some(t) = self.type_of(value) {
+                    if t.floating_point_kind().is_none() {
+                        self.error(format!("fneg needs a floating point value or vector of floating point values, not {t}"));
+                    }
+                }
+            }
+            Instruction::ExtractElement { vector, index } => {
+                if let Some(t) = self.type_of(vector) {
+                    if t.vector_shape().is_none() {
+                        self.error(format!("extractelement needs a vector, not {t}"));
+                    }
+                }
+
+                self.check_vector_index(index);
+            }
+            Instruction::InsertElement { vector, value, index } => {
+                if let Some(t) = self.type_of(vector) {
+                    match t {
+                        Type::Vector { element_type, .. } => self.expect_type(value, &element_type, "inserted element"),
+                        t => self.error(format!("insertelement needs a vector, not {t}")),
+                    }
+                }
+
+                self.check_vector_index(index);
+            }
+            Instruction::ShuffleVector { first, second, mask } => {
+                if let Some(t) = self.type_of(first) {
+                    self.expect_type(second, &t, "second vector");
+
+                    match t.vector_shape() {
+                        Some(shape) => self.check_shuffle_mask(mask, shape),
+                        None => self.error(format!("shufflevector needs vectors, not {t}")),
+                    }
+                }
+            }
+            Instruction::ExtractValue { aggregate, indices } => {
+                if instruction.get_result_type(self.definitions).is_none() {
+                    self.error(format!("invalid indices {indices:?} into {aggregate}"));
+                }
+            }
+            Instruction::InsertValue { aggregate, value, indices } => {
+                let extracted = Instruction::ExtractValue {
+                    aggregate: aggregate.clone(),
+                    indices: indices.clone(),
+                };
+
+                match extracted.get_result_type(self.definitions) {
+                    Some(element_type) => self.expect_type(value, &element_type, "inserted value"),
+                    None => self.error(format!("invalid indices {indices:?} into {aggregate}")),
+                }
+            }
+            Instruction::StackAllocate { value_type, num_elements, .. } => {
+                if !self.definitions.resolve_type(value_type).is_some_and(Type::is_sized) {
+                    self.error(format!("can't allocate unsized type {value_type}"));
+                }
+
+                if let Some(t) = num_elements.as_ref().and_then(|n| self.type_of(n)) {
+                    if !is_integer(&t) {
+                        self.error(format!("number of elements must be an integer, not {t}"));
+                    }
+                }
+            }
+            Instruction::Load { pointer, .. } | Instruction::Store { pointer, .. } => self.check_pointer(pointer),
+            Instruction::AtomicLoad { pointer, ordering, .. } => {
+                self.check_pointer(pointer);
+
+                if matches!(ordering, Ordering::Release | Ordering::AcquireRelease) {
+                    self.error(format!("atomic loads can't have {ordering} ordering"));
+                }
+            }
+            Instruction::AtomicStore { pointer, ordering, .. } => {
+                self.check_pointer(pointer);
+
+                if matches!(ordering, Ordering::Acquire | Ordering::AcquireRelease) {
+                    self.error(format!("atomic stores can't have {ordering} ordering"));
+                }
+            }
+            Instruction::Fence { ordering, .. } => {
+                if matches!(ordering, Ordering::Unordered | Ordering::Monotonic) {
+                    self.error(format!("fences can't have {ordering} ordering"));
+                }
+            }
+            Instruction::CompareExchange {
+                pointer,
+                compare_value,
+                new_value,
+                success_ordering,
+                failure_ordering,
+                ..
+            } => {
+                self.check_pointer(pointer);
+
+                if let Some(t) = self.type_of(compare_value) {
+                    if !is_integer(&t) && !is_pointer(&t) {
+                        self.error(format!("cmpxchg can only exchange integers or pointers, not {t}"));
+                    }
+
+                    self.expect_type(new_value, &t, "new value");
+                }
+
+                if matches!(success_ordering, Ordering::Unordered) || matches!(failure_ordering, Ordering::Unordered) {
+                    self.error("cmpxchg orderings must be at least monotonic".to_string());
+                }
+
+                if matches!(failure_ordering, Ordering::Release | Ordering::AcquireRelease) {
+                    self.error(format!("cmpxchg failure ordering can't be {failure_ordering}"));
+                }
+            }
+            Instruction::AtomicReadModifyWrite { operation, pointer, value, ordering, .. } => {
+                self.check_pointer(pointer);
+
+                if let Some(t) = self.type_of(value) {
+                    let is_valid = match operation {
+                        AtomicOperation::Exchange => is_integer(&t) || is_float(&t) || is_pointer(&t),
+                        AtomicOperation::FloatAdd | AtomicOperation::FloatSubtract | AtomicOperation::FloatMax | AtomicOperation::FloatMin => t.floating_point_kind().is_some(),
+                        _ => is_integer(&t),
+                    };
+
+                    if !is_valid {
+                        self.error(format!("atomicrmw {operation} can't operate on {t}"));
+                    }
+                }
+
+                if matches!(ordering, Ordering::Unordered) {
+                    self.error("atomicrmw ordering must be at least monotonic".to_string());
+                }
+            }
+            Instruction::GetElementPointer { pointer, indices, .. } => {
+                if let Some(t) = self.type_of(pointer) {
+                    if !is_pointer(t.scalar_type()) {
+                        self.error(format!("getelementptr needs a pointer or vector of pointers, not {t}"));
+                    }
+                }
+
+                for index in indices {
+                    if let Some(t) = self.type_of(index) {
+                        if t.integer_bit_width().is_none() {
+                            self.error(format!("getelementptr indices must be integers or vectors of integers, not {t}"));
+                        }
+                    }
+                }
+            }
+            Instruction::Truncate { value, new_type, .. } => self.check_integer_resize(value, new_type, false, "trunc"),
+            Instruction::ZeroExtend { value, new_type } => self.check_integer_resize(value, new_type, true, "zext"),
+            Instruction::SignExtend { value, new_type } => self.check_integer_resize(value, new_type, true, "sext"),
+            Instruction::FloatTruncate { value, new_type, .. } => self.check_float_resize(value, new_type, false, "fptrunc"),
+            Instruction::FloatExtend { value, new_type, .. } => self.check_float_resize(value, new_type, true, "fpext"),
+            Instruction::FloatToUnsignedInteger { value, new_type } => self.check_conversion(value, new_type, is_float, is_integer, "fptoui"),
+            Instruction::FloatToSignedInteger { value, new_type } => self.check_conversion(value, new_type, is_float, is_integer, "fptosi"),
+            Instruction::UnsignedIntegerToFloat { value, new_type } => self.check_conversion(value, new_type, is_integer, is_float, "uitofp"),
+            Instruction::SignedIntegerToFloat { value, new_type } => self.check_conversion(value, new_type, is_integer, is_float, "sitofp"),
+            Instruction::PointerToInteger { value, new_type } => self.check_conversion(value, new_type, is_pointer, is_integer, "ptrtoint"),
+            Instruction::IntegerToPointer { value, new_type } => self.check_conversion(value, new_type, is_integer, is_pointer, "inttoptr"),
+            Instruction::AddressSpaceCast { value, new_type } => {
+                self.check_conversion(value, new_type, is_pointer, is_pointer, "addrspacecast");
+
+                if self.type_of(value).as_ref() == Some(new_type) {
+                    self.error("addrspacecast must change the address space of its operand".to_string());
+                }
+            }
+            Instruction::BitCast { value, new_type } => {
+                if let Some(old_type) = self.type_of(value) {
+                    if is_pointer(old_type.scalar_type()) != is_pointer(new_type.scalar_type()) {
+                        self.error(format!("bitcast can't convert between pointers and non-pointers ({old_type} to {new_type})"));
+                    } else if !old_type.is_first_class() || !new_type.is_first_class() {
+                        self.error(format!("bitcast can only convert between first-class types, not {old_type} and {new_type}"));
+                    }
+                }
+            }
+            Instruction::CompareIntegers { left_hand_side, right_hand_side, .. } => {
+                if let (Some(left), Some(right)) = (self.type_of(left_hand_side), self.type_of(right_hand_side)) {
+                    if left != right {
+                        self.error(format!("operand types {left} and {right} don't match"));
+                    } else if !is_integer(left.scalar_type()) && !is_pointer(left.scalar_type()) {
+                        self.error(format!("icmp can only compare integers or pointers, not {left}"));
+                    }
+                }
+            }
+            Instruction::CompareFloats { left_hand_side, right_hand_side, .. } => self.check_float_operands(left_hand_side, right_hand_side),
+            Instruction::Select { condition, true_value, false_value, .. } => {
+                if let (Some(condition), Some(t)) = (self.type_of(condition), self.type_of(true_value)) {
+                    if condition.scalar_type() != &(Type::Integer { bit_width: 1 }) || (condition.vector_shape().is_some() && condition.vector_shape() != t.vector_shape()) {
+                        self.error(format!("select condition must be i1 or a vector of i1 matching the values, not {condition}"));
+                    }
+
+                    self.expect_type(false_value, &t, "false value");
+                }
+            }
+            Instruction::Phi { value_type, .. } => {
+                if !self.definitions.resolve_type(value_type).is_some_and(|t| t.is_first_class() || t.is_sized()) {
+                    self.error(format!("phi nodes can't have type {value_type}"));
+                }
+            }
+            Instruction::Freeze { .. } => (),
+            Instruction::Call {
+                function_type,
+                function_arguments,
+                attribute_groups,
+                operand_bundles,
+                ..
+            } => {
+                self.check_arguments(function_type, function_arguments);
+                self.check_attribute_groups(attribute_groups);
+                self.check_operand_bundles(operand_bundles);
+            }
+            Instruction::CallAssembly { attribute_groups, .. } => self.check_attribute_groups(attribute_groups),
+            Instruction::CatchPad { .. } | Instruction::CleanupPad { .. } => (),
+            Instruction::LandingPad { is_cleanup, clauses, .. } => {
+                if !is_cleanup && clauses.is_empty() {
+                    self.error("landingpad needs at least one clause if it isn't a cleanup".to_string());
+                }
+
+                for clause in clauses {
+                    if let LandingPadClause::Filter(value) = clause {
+                        if let Some(t) = self.type_of(value) {
+                            if !matches!(t, Type::Array { .. }) {
+                                self.error(format!("filter clauses need an array, not {t}"));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// makes sure the arguments of a call match the parameters of the function type it's called with
+    fn check_arguments(&mut self, function_type: &Type, function_arguments: &[CallArgument]) {
+        if let Type::Function { parameters, has_varargs, .. } = function_type {
+            if function_arguments.len() < parameters.len() || (!has_varargs && function_arguments.len() > parameters.len()) {
+                self.error(format!("expected {} arguments, got {}", parameters.len(), function_arguments.len()));
+            }
+
+            for (argument, parameter) in function_arguments.iter().zip(parameters) {
+                self.expect_type(&argument.value, parameter, "argument");
+            }
+        }
+    }
+
+    /// makes sure a call doesn't have more than one of the operand bundles that can only be given once, and that `funclet` bundles only have a token
+    fn check_operand_bundles(&mut self, operand_bundles: &[OperandBundle]) {
+        for (index, bundle) in operand_bundles.iter().enumerate() {
+            let is_unique = matches!(bundle.tag.as_str(), "deopt" | "funclet" | "gc-transition" | "gc-live" | "cfguardtarget" | "preallocated" | "ptrauth" | "kcfi" | "convergencectrl");
+
+            if is_unique && operand_bundles[..index].iter().any(|other| other.tag == bundle.tag) {
+                self.error(format!("multiple {} operand bundles", bundle.tag));
+            }
+
+            if bundle.tag == "funclet" {
+                match bundle.inputs.as_slice() {
+                    [input] => self.expect_type(input, &Type::Token, "funclet operand bundle input"),
+                    _ => self.error(format!("funclet operand bundles need exactly one input, got {}", bundle.inputs.len())),
+                }
+            }
+        }
+    }
+}
+
+/// checks the rules for a single function
+struct FunctionVerifier<'a> {
+    function: &'a Function,
+    values: ValueVerifier<'a>,
+    /// the labels of each block in the function, including the `%` prefix
+    block_labels: Vec<String>,
+    /// the labels of the blocks that can branch to each block
+    predecessors: Vec<Vec<String>>,
+    /// the block and index in it that each named instruction result is defined at
+    definition_sites: HashMap<String, (usize, usize)>,
+    /// which blocks dominate each block, or `None` for blocks that can't be reached from the entry block, since like in LLVM anything goes in those
+    dominators: Vec<Option<Vec<bool>>>,
+}
+
+impl<'a> FunctionVerifier<'a> {
+    fn new(function: &'a Function, definitions: &'a dyn TypeDefinitions, module: Option<&'a Module>) -> Self {
+        Self {
+            function,
+            values: ValueVerifier {
+                definitions,
+                module,
+                values: HashMap::new(),
+                location: Location::Global { name: function.header.name.clone() },
+                errors: Vec::new(),
+            },
+            block_labels: Vec::new(),
+            predecessors: Vec::new(),
+            definition_sites: HashMap::new(),
+            dominators: Vec::new(),
+        }
+    }
+
+    fn error(&mut self, message: String) {
+        self.values.error(message);
+    }
+
+    fn verify(mut self) -> Vec<VerifierError> {
+        self.define_values();
+        self.find_predecessors();
+        self.find_dominators();
+
+        if self.function.basic_blocks.is_empty() {
+            self.error("function has no basic blocks".to_string());
+        }
+
+        self.values.check_attribute_groups(&self.function.header.attribute_groups);
+        self.values.check_metadata_attachments(&self.function.header.metadata);
+
+        for (block_index, block) in self.function.basic_blocks.iter().enumerate() {
+            let mut can_be_phi = true;
+
+            for (index, operation) in block.operations.iter().enumerate() {
+                self.values.location = self.instruction_location(block_index, index);
+
+                let (instruction, metadata) = match operation {
+                    Operation::Assignment { value, metadata, .. } => (value, metadata),
+                    Operation::NoAssignment { instruction, metadata } => (instruction, metadata),
+                };
+
+                self.values.check_metadata_attachments(metadata);
+
+                // phi nodes refer to blocks without branching to them
+                for operand in instruction.operands() {
+                    self.values.check_value(operand);
+                }
+
+                if let Instruction::Phi { incoming, .. } = instruction {
+                    if !can_be_phi {
+                        self.error("phi nodes must come before every other instruction in their block".to_string());
+                    }
+
+                    self.check_incoming_blocks(block_index, incoming);
+                } else {
+                    for operand in instruction.operands() {
+                        self.check_dominance(operand, block_index, index);
+                    }
+
+                    if instruction.is_exception_handling_pad() {
+                        self.check_pad_placement(can_be_phi);
+                    }
+
+                    can_be_phi = false;
+                }
+
+                self.values.check_instruction(instruction);
+            }
+
+            self.values.location = self.instruction_location(block_index, block.operations.len());
+
+            for operand in block.terminator.operands() {
+                self.check_operand(operand);
+                self.check_dominance(operand, block_index, block.operations.len());
+            }
+
+            self.values.check_metadata_attachments(&block.terminator_metadata);
+
+            if matches!(block.terminator, Terminator::CatchSwitch { .. }) {
+                self.check_pad_placement(can_be_phi);
+            }
+
+            self.check_terminator(&block.terminator);
+        }
+
+        self.values.errors
+    }
+
+    /// makes sure an exception handling pad is in a function with a personality and comes right after the phi nodes in its block
+    fn check_pad_placement(&mut self, is_after_phi_nodes: bool) {
+        if self.function.header.personality.is_none() {
+            self.error("exception handling pads can only be used in functions with a personality".to_string());
+        }
+
+        if !is_after_phi_nodes {
+            self.error("exception handling pads must be the first instruction in their block after any phi nodes".to_string());
+        }
+    }
+
+    fn instruction_location(&self, block_index: usize, index: usize) -> Location {
+        Location::Instruction {
+            function: self.function.header.name.clone(),
+            block: self.block_labels[block_index].clone(),
+            index,
+        }
+    }
+
+    /// gives a name to an unnamed value, or makes sure a numbered value is numbered correctly
+    fn number_value(&mut self, name: Option<String>, next_number: &mut usize) -> String {
+        let Some(name) = name else {
+            *next_number += 1;
+            return format!("%{}", *next_number - 1);
+        };
+
+        if let Ok(number) = name[1..].parse::<usize>() {
+            if number != *next_number {
+                self.error(format!("{name} should be numbered %{next_number}"));
+            }
+
+            *next_number = number + 1;
+        }
+
+        name
+    }
+
+    fn define_value(&mut self, name: String, t: Type) {
+        match self.values.values.entry(name) {
+            Entry::Occupied(entry) => {
+                let message = format!("multiple definitions of {}", entry.key());
+                self.error(message);
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(t);
+            }
+        }
+    }
+
+    /// finds the names and types of every argument, block and instruction result in the function
+    fn define_values(&mut self) {
+        let mut next_number = 0;
+
+        for argument in &self.function.header.arguments {
+            let name = self.number_value(argument.name.clone(), &mut next_number);
+            self.define_value(name, argument.parameter_type.clone());
+        }
+
+        for (block_index, block) in self.function.basic_blocks.iter().enumerate() {
+            let label = self.number_value(block.name.as_ref().map(|name| format!("%{name}")), &mut next_number);
+            self.block_labels.push(label.clone());
+            self.values.location = Location::Global {
+                name: self.function.header.name.clone(),
+            };
+            self.define_value(label, Type::Label);
+
+            for (index, operation) in block.operations.iter().enumerate() {
+                self.values.location = self.instruction_location(block_index, index);
+
+                let (identifier, instruction) = match operation {
+                    Operation::Assignment { identifier, value, .. } => (Some(identifier), value),
+                    Operation::NoAssignment { instruction, .. } => (None, instruction),
+                };
+
+                self.define_result(identifier, instruction.get_result_type(self.values.definitions), &mut next_number);
+
+                if let Some(identifier) = identifier {
+                    self.definition_sites.entry(identifier.clone()).or_insert((block_index, index));
+                }
+            }
+
+            self.values.location = self.instruction_location(block_index, block.operations.len());
+            self.define_result(block.terminator_identifier.as_ref(), Some(block.terminator.get_result_type()), &mut next_number);
+
+            if let Some(identifier) = &block.terminator_identifier {
+                self.definition_sites.entry(identifier.clone()).or_insert((block_index, block.operations.len()));
+            }
+        }
+    }
+
+    /// names or numbers the result of an instruction or terminator, where `identifier` is what it's assigned to if anything
+    fn define_result(&mut self, identifier: Option<&String>, result_type: Option<Type>, next_number: &mut usize) {
+        match identifier {
+            Some(identifier) => {
+                let name = self.number_value(Some(identifier.clone()), next_number);
+
+                match result_type {
+                    Some(Type::Void) => self.error(format!("{name} can't be assigned an instruction that doesn't produce a value")),
+                    Some(t) => self.define_value(name, t),
+                    None => self.error(format!("couldn't determine the type of {name}")),
+                }
+            }
+            // unnamed values still take up a number
+            None => {
+                if !matches!(result_type, Some(Type::Void) | None) {
+                    self.number_value(None, next_number);
+                }
+            }
+        }
+    }
+
+    /// finds the blocks that can branch to each block from the destinations of their terminators
+    fn find_predecessors(&mut self) {
+        self.predecessors = vec![Vec::new(); self.function.basic_blocks.len()];
+
+        for (block, label) in self.function.basic_blocks.iter().zip(&self.block_labels) {
+            for operand in block.terminator.operands() {
+                let Value::FromIdentifier { value_type: Type::Label, identifier } = operand.as_ref() else {
+                    continue;
+                };
+
+                if let Some(index) = self.block_labels.iter().position(|l| l == identifier) {
+                    if !self.predecessors[index].contains(label) {
+                        self.predecessors[index].push(label.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    /// finds the blocks that dominate each block, which are the blocks every path from the entry block to it goes through
+    fn find_dominators(&mut self) {
+        let count = self.function.basic_blocks.len();
+        let predecessors = self
+            .predecessors
+            .iter()
+            .map(|labels| labels.iter().filter_map(|label| self.block_labels.iter().position(|l| l == label)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let mut is_reachable = vec![false; count];
+        let mut stack = if count == 0 { Vec::new() } else { vec![0] };
+
+        while let Some(block) = stack.pop() {
+            if !std::mem::replace(&mut is_reachable[block], true) {
+                stack.extend((0..count).filter(|&successor| predecessors[successor].contains(&block)));
+            }
+        }
+
+        self.dominators = is_reachable.iter().map(|&is_reachable| is_reachable.then(|| vec![true; count])).collect();
+
+        if let Some(Some(entry)) = self.dominators.first_mut() {
+            *entry = (0..count).map(|block| block == 0).collect();
+        }
+
+        // the entry block is only dominated by itself, and every other block is dominated by itself and whatever dominates all of its reachable predecessors
+        let mut has_changed = true;
+
+        while has_changed {
+            has_changed = false;
+
+            for block in 1..count {
+                let Some(current) = &self.dominators[block] else {
+                    continue;
+                };
+
+                let mut dominators = vec![true; count];
+
+                for &predecessor in &predecessors[block] {
+                    if let Some(of_predecessor) = &self.dominators[predecessor] {
+                        dominators.iter_mut().zip(of_predecessor).for_each(|(dominator, &is_dominator)| *dominator &= is_dominator);
+                    }
+                }
+
+                dominators[block] = true;
+
+                if &dominators != current {
+                    self.dominators[block] = Some(dominators);
+                    has_changed = true;
+                }
+            }
+        }
+    }
+
+    /// makes sure a value defined by an instruction is only used where its definition has already run, which is after it in the same block or in a block it dominates
+    fn check_dominance(&mut self, value: &Value, block_index: usize, index: usize) {
+        let Value::FromIdentifier { identifier, .. } = value else {
+            return;
+        };
+
+        let (Some(&(definition_block, definition_index)), Some(dominators)) = (self.definition_sites.get(identifier), &self.dominators[block_index]) else {
+            return;
+        };
+
+        let dominates = if definition_block == block_index { definition_index < index } else { dominators[definition_block] };
+
+        if !dominates {
+            self.error(format!("{identifier} is used somewhere its definition doesn't dominate"));
+        }
+    }
+
+    /// makes sure a phi node has exactly one incoming value for each predecessor of its block
+    fn check_incoming_blocks(&mut self, block_index: usize, incoming: &[IncomingValue]) {
+        let mut seen = HashMap::<&str, &Value>::new();
+
+        for IncomingValue { value, block } in incoming {
+            let Value::FromIdentifier { identifier, .. } = block.as_ref() else {
+                continue;
+            };
+
+            if !self.predecessors[block_index].contains(identifier) {
+                let message = format!("{identifier} isn't a predecessor of {}", self.block_labels[block_index]);
+                self.error(message);
+            }
+
+            // incoming values are used when the block they come from branches here, so they only have to be defined by the end of it
+            if let Some(predecessor) = self.block_labels.iter().position(|label| label == identifier) {
+                let end = self.function.basic_blocks[predecessor].operations.len() + 1;
+                self.check_dominance(value, predecessor, end);
+            }
+
+            // the same block can be listed more than once, as long as the value is the same
+            if seen.insert(identifier, value).is_some_and(|other| other != value.as_ref()) {
+                self.error(format!("phi node has different values for {identifier}"));
+            }
+        }
+
+        for predecessor in self.predecessors[block_index].clone() {
+            if !seen.contains_key(predecessor.as_str()) {
+                self.error(format!("phi node has no value for predecessor {predecessor}"));
+            }
+        }
+    }
+
+    /// finds what kind of exception handling pad the block with the given label starts with after its phi nodes, if any
+    fn pad_at(&self, label: &Value) -> Option<&'static str> {
+        let Value::FromIdentifier { identifier, .. } = label else {
+            return None;
+        };
+
+        let block = &self.function.basic_blocks[self.block_labels.iter().position(|l| l == identifier)?];
+        let first = block
+            .operations
+            .iter()
+            .map(|operation| match operation {
+                Operation::Assignment { value, .. } => value,
+                Operation::NoAssignment { instruction, .. } => instruction,
+            })
+            .find(|instruction| !matches!(instruction, Instruction::Phi { .. }));
+
+        match (first, &block.terminator) {
+            (Some(Instruction::LandingPad { .. }), _) => Some("landingpad"),
+            (Some(Instruction::CatchPad { .. }), _) => Some("catchpad"),
+            (Some(Instruction::CleanupPad { .. }), _) => Some("cleanuppad"),
+            (None, Terminator::CatchSwitch { .. }) => Some("catchswitch"),
+            _ => None,
+        }
+    }
+
+    /// makes sure a block that's branched to during unwinding starts with one of the `allowed` pads, describing it as `what` if it doesn't
+    fn check_pad_at(&mut self, label: &Value, allowed: &[&str], what: &str) {
+        if !self.pad_at(label).is_some_and(|pad| allowed.contains(&pad)) {
+            self.error(format!("{what} {label} must start with {}", allowed.join(" or ")));
+        }
+    }
+
+    fn check_operand(&mut self, operand: &Arc<Value>) {
+        self.values.check_value(operand);
+
+        // the entry block is where the function starts, so it can't be jumped to from anywhere else
+        if let Value::FromIdentifier { value_type: Type::Label, identifier } = operand.as_ref() {
+            if self.block_labels.first() == Some(identifier) {
+                self.error(format!("the entry block {identifier} can't be branched to"));
+            }
+        }
+    }
+
+    fn type_of(&mut self, value: &Value) -> Option<Type> {
+        self.values.type_of(value)
+    }
+
+    fn check_terminator(&mut self, terminator: &Terminator) {
+        match terminator {
+            Terminator::Return { value } => {
+                let return_type = self.function.header.return_type.clone();
+                self.values.expect_type(value, &return_type, "return value");
+            }
+            Terminator::ConditionalBranch { condition, .. } => self.values.expect_type(condition, &Type::Integer { bit_width: 1 }, "branch condition"),
+            Terminator::Switch { value, destinations, .. } => {
+                if let Some(t) = self.type_of(value) {
+                    if !matches!(t, Type::Integer { .. }) {
+                        self.error(format!("switch value must be an integer, not {t}"));
+                    }
+
+                    for destination in destinations {
+                        self.values.expect_type(&destination.value, &t, "case value");
+                    }
+                }
+            }
+            Terminator::IndirectBranch { address, .. } => self.values.check_pointer(address),
+            Terminator::Invoke {
+                function_type,
+                function_arguments,
+                attribute_groups,
+                operand_bundles,
+                unwind_destination,
+                ..
+            } => {
+                self.values.check_arguments(function_type, function_arguments);
+                self.values.check_attribute_groups(attribute_groups);
+                self.values.check_operand_bundles(operand_bundles);
+                self.check_pad_at(unwind_destination, &["landingpad", "cleanuppad", "catchswitch"], "unwind destination");
+            }
+            Terminator::CatchSwitch { handlers, unwind_destination, .. } => {
+                for handler in handlers {
+                    self.check_pad_at(handler, &["catchpad"], "catchswitch handler");
+                }
+
+                if let Some(destination) = unwind_destination {
+                    self.check_pad_at(destination, &["cleanuppad", "catchswitch"], "unwind destination");
+                }
+            }
+            Terminator::CleanupReturn {
+                unwind_destination: Some(destination),
+                ..
+            } => self.check_pad_at(destination, &["cleanuppad", "catchswitch"], "unwind destination"),
+            Terminator::CallBranch { attribute_groups, .. } => self.values.check_attribute_groups(attribute_groups),
+            Terminator::Branch { .. } | Terminator::Resume { .. } | Terminator::CatchReturn { .. } | Terminator::CleanupReturn { .. } | Terminator::Unreachable => (),
+        }
+    }
+}
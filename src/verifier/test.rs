@@ -0,0 +1,587 @@
+use super::{verify_function, verify_module, Location};
+use crate::llvm::grammar::{FunctionParser, ModuleParser};
+
+/// makes sure well-formed code passes verification, including implicitly numbered values and references to globals
+#[test]
+fn valid_code() {
+    let module = ModuleParser::new()
+        .parse(
+            r#"%pair = type { i32, i32 }
+
+@x = global i32 0
+@p = global %pair { i32 1, i32 2 }
+
+declare i32 @g(i32)
+
+define i32 @f(i32 %0, ptr) {
+    %3 = add i32 %0, 1
+    %4 = trunc i32 %3 to i8
+    %5 = zext i8 %4 to i64
+    %6 = load atomic i32, ptr @x acquire, align 4
+    %7 = icmp eq i32 %3, %6
+    %8 = select i1 %7, i32 %3, i32 %6
+    call i32 @g(i32 %8)
+    %10 = extractvalue %pair { i32 1, i32 2 }, 1
+    br label %next
+
+next:
+    store atomic i32 %10, ptr %1 release, align 4
+    ret i32 %8
+}
+"#,
+        )
+        .unwrap();
+
+    assert!(verify_module(&module) == Ok(()));
+}
+
+/// makes sure mistakes are reported with the function, block and instruction they're in
+#[test]
+fn invalid_code() {
+    let function = FunctionParser::new()
+        .parse(
+            r#"define i32 @f(i32 %a, i64 %b) {
+entry:
+    %c = add i32 %a, %b
+    %d = trunc i32 %a to i64
+    %e = zext i32 %a to i16
+    %c = select i32 %a, i32 %a, i32 %a
+    %f = load atomic i32, ptr %g release, align 4
+    br label %entry
+}"#,
+        )
+        .unwrap();
+
+    let errors = verify_function(&function, &()).unwrap_err();
+    let at = |index| Location::Instruction {
+        function: "@f".to_string(),
+        block: "%entry".to_string(),
+        index,
+    };
+
+    // the redefinition of %c is found before anything else
+    assert!(errors[0].location == at(3));
+    assert!(errors[0].message.contains("multiple definitions of %c"));
+
+    let errors_at = |index| errors.iter().filter(|e| e.location == at(index)).count();
+    assert!(errors_at(0) == 1);
+    assert!(errors_at(1) == 1);
+    assert!(errors_at(2) == 1);
+    // the redefinition and the condition
+    assert!(errors_at(3) == 2);
+    // the undefined pointer and the ordering
+    assert!(errors_at(4) == 2);
+    // the branch to the entry block
+    assert!(errors_at(5) == 1);
+    assert!(errors.len() == 8);
+}
+
+/// makes sure implicit numbering is checked, and that globals and their initializers are checked in modules
+#[test]
+fn numbering_and_globals() {
+    let module = ModuleParser::new()
+        .parse(
+            r#"@x = global i32 0
+@y = global ptr @z
+@w = external global i32
+@v = internal global i32
+
+define void @f(i32) {
+    %3 = add i32 %0, %0
+    store i32 %3, ptr @x
+    ret void
+}
+"#,
+        )
+        .unwrap();
+
+    let errors = verify_module(&module).unwrap_err();
+
+    assert!(errors.len() == 3);
+    assert!(errors[0].location == Location::Global { name: "@y".to_string() });
+    assert!(errors[0].message.contains("@z"));
+    // only globals from other modules can leave out their initializer
+    assert!(errors[1].to_string() == "@v: global variables without an initializer must have external or extern_weak linkage, not internal");
+    assert!(errors[2].message.contains("%3 should be numbered %2"));
+    // printing errors doesn't need any special treatment
+    assert!(errors[2].to_string().starts_with("@f, block %1, instruction 0: "));
+}
+
+/// makes sure floating point arithmetic is only done on floating point values of the same type
+#[test]
+fn float_arithmetic() {
+    let function = FunctionParser::new()
+        .parse(
+            r#"define float @f(float %a, double %b, i32 %c, <2 x float> %d) {
+    %e = fadd fast float %a, %a
+    %f = fmul <2 x float> %d, %d
+    %g = fsub float %a, %b
+    %h = fdiv i32 %c, %c
+    %i = fneg i32 %c
+    ret float %e
+}"#,
+        )
+        .unwrap();
+
+    let errors = verify_function(&function, &()).unwrap_err();
+
+    assert!(errors.len() == 3);
+    assert!(errors.iter().zip(2..).all(|(error, index)| error.location
+        == Location::Instruction {
+            function: "@f".to_string(),
+            block: "%0".to_string(),
+            index,
+        }));
+}
+
+/// makes sure floating point comparisons and conversions only accept the types they're meant to
+#[test]
+fn float_conversions() {
+    let function = FunctionParser::new()
+        .parse(
+            r#"define void @f(float %a, double %b, i32 %c, <2 x float> %d, <2 x i64> %e, half %h, bfloat %r) {
+    %v0 = fcmp olt float %a, 0.5
+    %v1 = fpext <2 x float> %d to <2 x double>
+    %v2 = fptrunc double %b to half
+    %v3 = fptosi <2 x float> %d to <2 x i64>
+    %v4 = uitofp i32 %c to double
+    %v5 = fcmp ueq i32 %c, %c
+    %v6 = fcmp oeq float %a, %b
+    %v7 = fptrunc float %a to double
+    %v8 = fpext half %h to bfloat
+    %v9 = fptoui i32 %c to i32
+    %v10 = sitofp <2 x i64> %e to float
+    ret void
+}"#,
+        )
+        .unwrap();
+
+    let errors = verify_function(&function, &()).unwrap_err();
+
+    assert!(errors.len() == 6);
+    assert!(errors.iter().zip(5..).all(|(error, index)| error.location
+        == Location::Instruction {
+            function: "@f".to_string(),
+            block: "%0".to_string(),
+            index,
+        }));
+}
+
+/// makes sure phi nodes come first and have one value for each predecessor of their block
+#[test]
+fn phi_nodes() {
+    let valid = FunctionParser::new()
+        .parse(
+            r#"define i32 @f(i32 %n) {
+entry:
+    br label %loop
+
+loop:
+    %i = phi i32 [ 0, %entry ], [ %next, %loop ]
+    %next = add i32 %i, 1
+    %done = icmp eq i32 %next, %n
+    br i1 %done, label %exit, label %loop
+
+exit:
+    %r = phi i32 [ %next, %loop ], [ %next, %loop ]
+    ret i32 %r
+}"#,
+        )
+        .unwrap();
+
+    assert!(verify_function(&valid, &()).is_ok());
+
+    let invalid = FunctionParser::new()
+        .parse(
+            r#"define i32 @f(i1 %c) {
+entry:
+    br i1 %c, label %a, label %b
+
+a:
+    br label %b
+
+b:
+    %x = add i32 1, 2
+    %y = phi i32 [ 1, %a ], [ 2, %a ], [ 3, %b ]
+    %z = phi i64 [ %x, %a ], [ 0, %entry ]
+    ret i32 %y
+}"#,
+        )
+        .unwrap();
+
+    let errors = verify_function(&invalid, &()).unwrap_err();
+    let messages = errors.iter().map(|error| error.message.as_str()).collect::<Vec<_>>();
+
+    assert!(
+        messages
+            == [
+                "phi nodes must come before every other instruction in their block",
+                "phi node has different values for %a",
+                "%b isn't a predecessor of %b",
+                "phi node has no value for predecessor %entry",
+                "%x is defined with type i32 but used as i64",
+                "phi nodes must come before every other instruction in their block",
+                "%x is used somewhere its definition doesn't dominate",
+            ]
+    );
+}
+
+/// makes sure values are only used where their definition is guaranteed to have run, except in blocks that can't be reached
+#[test]
+fn dominance() {
+    let function = FunctionParser::new()
+        .parse(
+            r#"define i32 @f(i1 %c) {
+entry:
+    %a = add i32 %b, 1
+    %b = add i32 1, 1
+    %s = add i32 %s, 1
+    br i1 %c, label %left, label %right
+
+left:
+    %l = add i32 %a, 1
+    br label %join
+
+right:
+    br label %join
+
+join:
+    %p = phi i32 [ %l, %left ], [ %l, %right ]
+    %q = add i32 %l, %p
+    ret i32 %q
+
+dead:
+    %d = add i32 %d, %e
+    %e = add i32 %l, 1
+    br label %dead
+}"#,
+        )
+        .unwrap();
+
+    let errors = verify_function(&function, &()).unwrap_err();
+    let at = |block: &str, index| Location::Instruction {
+        function: "@f".to_string(),
+        block: block.to_string(),
+        index,
+    };
+    let found = errors.iter().map(|error| (error.location.clone(), error.message.as_str())).collect::<Vec<_>>();
+
+    // %l is only defined on one of the paths to %join, and the phi node's value for %right is used at the end of %right
+    assert!(
+        found
+            == [
+                (at("%entry", 0), "%b is used somewhere its definition doesn't dominate"),
+                (at("%entry", 2), "%s is used somewhere its definition doesn't dominate"),
+                (at("%join", 0), "%l is used somewhere its definition doesn't dominate"),
+                (at("%join", 1), "%l is used somewhere its definition doesn't dominate"),
+            ]
+    );
+}
+
+/// makes sure cmpxchg and atomicrmw check their operand types and orderings
+#[test]
+fn atomic_instructions() {
+    let function = FunctionParser::new()
+        .parse(
+            r#"define void @f(ptr %p, i32 %x, float %y) {
+    %a = cmpxchg ptr %p, i32 %x, i32 1 acq_rel monotonic
+    %b = atomicrmw add ptr %p, i32 %x seq_cst
+    %c = atomicrmw fadd ptr %p, float %y monotonic
+    %d = atomicrmw xchg ptr %p, ptr %p monotonic
+    %e = cmpxchg ptr %p, float %y, float %y seq_cst seq_cst
+    %f = cmpxchg ptr %p, i32 %x, i32 %x monotonic release
+    %g = atomicrmw add ptr %p, float %y monotonic
+    %h = atomicrmw fmax ptr %p, i32 %x unordered
+    %i = cmpxchg i32 %x, i32 %x, i64 0 monotonic monotonic
+    ret void
+}"#,
+        )
+        .unwrap();
+
+    let errors = verify_function(&function, &()).unwrap_err();
+    let messages = errors.iter().map(|error| error.message.as_str()).collect::<Vec<_>>();
+
+    assert!(
+        messages
+            == [
+                "cmpxchg can only exchange integers or pointers, not float",
+                "cmpxchg failure ordering can't be release",
+                "atomicrmw add can't operate on float",
+                "atomicrmw fmax can't operate on i32",
+                "atomicrmw ordering must be at least monotonic",
+                "expected a pointer, got i32",
+                "new value has type i64, but i32 is required",
+            ]
+    );
+}
+
+/// makes sure vector element instructions are given vectors, integer indices and masks that stay in range
+#[test]
+fn vector_instructions() {
+    let function = FunctionParser::new()
+        .parse(
+            r#"define void @f(<4 x i32> %v, <vscale x 2 x i64> %s, i32 %x, float %y) {
+    %a = extractelement <4 x i32> %v, i64 %x
+    %b = insertelement <4 x i32> %v, i32 %x, i32 3
+    %c = shufflevector <4 x i32> %v, <4 x i32> poison, <3 x i32> <i32 7, i32 undef, i32 poison>
+    %d = shufflevector <vscale x 2 x i64> %s, <vscale x 2 x i64> %s, <vscale x 2 x i32> zeroinitializer
+    %e = extractelement i32 %x, i32 0
+    %f = insertelement <4 x i32> %v, float %y, i32 0
+    %g = extractelement <4 x i32> %v, float %y
+    %h = shufflevector <4 x i32> %v, <4 x i32> %v, <2 x i32> <i32 8, i32 %x>
+    %i = shufflevector <vscale x 2 x i64> %s, <vscale x 2 x i64> %s, <vscale x 2 x i32> <i32 0, i32 1>
+    %j = shufflevector <4 x i32> %v, <4 x i32> %v, <2 x i64> zeroinitializer
+    %k = shufflevector <4 x i32> %v, <vscale x 2 x i64> %s, <vscale x 4 x i32> poison
+    ret void
+}"#,
+        )
+        .unwrap();
+
+    let errors = verify_function(&function, &()).unwrap_err();
+    let messages = errors.iter().map(|error| error.message.as_str()).collect::<Vec<_>>();
+
+    assert!(
+        messages
+            == [
+                "couldn't determine the type of %e",
+                "%x is defined with type i32 but used as i64",
+                "extractelement needs a vector, not i32",
+                "inserted element has type float, but i32 is required",
+                "vector indices must be integers, not float",
+                "shufflevector mask elements must be constants below 8, not i32 8",
+                "shufflevector mask elements must be constants below 8, not i32 %x",
+                "masks for scalable vectors can only be zeroinitializer, undef or poison",
+                "shufflevector mask must be a vector of i32, not <2 x i64>",
+                "second vector has type <vscale x 2 x i64>, but <4 x i32> is required",
+                "shufflevector mask <vscale x 4 x i32> poison must be scalable if and only if the vectors are",
+            ]
+    );
+}
+
+/// makes sure exception handling pads are only used with a personality, at the start of the blocks that are unwound to
+#[test]
+fn exception_handling() {
+    let valid = FunctionParser::new()
+        .parse(
+            r#"define void @f() personality ptr @personality {
+entry:
+    %r = invoke i32 @g(i32 1) to label %ok unwind label %lpad
+
+ok:
+    %s = add i32 %r, 1
+    ret void
+
+lpad:
+    %lp = landingpad { ptr, i32 } cleanup
+    resume { ptr, i32 } %lp
+}"#,
+        )
+        .unwrap();
+
+    assert!(verify_function(&valid, &()).is_ok());
+
+    let invalid = FunctionParser::new()
+        .parse(
+            r#"define void @f(i32 %x) {
+entry:
+    invoke void (i32) @g(i32 %x, i32 %x) to label %ok unwind label %ok
+
+ok:
+    %a = add i32 %x, 1
+    %lp = landingpad { ptr, i32 } filter ptr null
+    ret void
+
+dispatch:
+    %cs = catchswitch within none [label %ok] unwind to caller
+
+cleanup:
+    %cl = cleanuppad within none []
+    cleanupret from %cl unwind label %cleanup
+}"#,
+        )
+        .unwrap();
+
+    let errors = verify_function(&invalid, &()).unwrap_err();
+    let messages = errors.iter().map(|error| error.message.as_str()).collect::<Vec<_>>();
+
+    assert!(
+        messages
+            == [
+                "expected 1 arguments, got 2",
+                "unwind destination label %ok must start with landingpad or cleanuppad or catchswitch",
+                "exception handling pads can only be used in functions with a personality",
+                "exception handling pads must be the first instruction in their block after any phi nodes",
+                "filter clauses need an array, not ptr",
+                "exception handling pads can only be used in functions with a personality",
+                "catchswitch handler label %ok must start with catchpad",
+                "exception handling pads can only be used in functions with a personality",
+            ]
+    );
+}
+
+/// makes sure constant expressions only use constants and globals, have the type they're used as, and follow the same rules as instructions
+#[test]
+fn constant_expressions() {
+    let function = FunctionParser::new()
+        .parse(
+            r#"define i64 @f(i64 %x) {
+    %a = add i64 ptrtoint (ptr @g to i64), 1
+    %b = add i64 add (i64 %x, i64 1), 1
+    ret i64 %a
+}"#,
+        )
+        .unwrap();
+
+    let errors = verify_function(&function, &()).unwrap_err();
+
+    assert!(errors.len() == 1);
+    assert!(errors[0].message == "constant expressions can only use constants and globals, not i64 %x");
+    assert!(FunctionParser::new().parse("define void @f() {\n    store i32 ptrtoint (ptr @g to i64), ptr @g\n    ret void\n}").is_err());
+
+    // the instructions in constant expressions are checked like any other, including ones nested in other constant expressions
+    let module = ModuleParser::new()
+        .parse(
+            r#"@a = global i32 add (i32 1, i64 2)
+@b = global i32 sdiv (i32 -2147483648, i32 -1)
+@c = global i32 urem (i32 1, i32 0)
+@d = global i32 add (i32 1, i32 trunc (i32 1 to i32))
+@e = global i64 add (i64 ptrtoint (ptr @a to i64), i64 1)
+@f = global i32 udiv (i32 add (i32 1, i64 2), i32 1)
+"#,
+        )
+        .unwrap();
+
+    let errors = verify_module(&module).unwrap_err();
+
+    assert!(
+        errors.iter().map(|error| error.to_string()).collect::<Vec<_>>()
+            == [
+                "@a: operand types i32 and i64 don't match",
+                "@b: constant expression i32 sdiv (i32 -2147483648, i32 -1) overflows",
+                "@c: constant expression i32 urem (i32 1, i32 0) divides by zero",
+                "@d: trunc must make its operand narrower, but i32 is at most as wide as i32",
+                "@f: operand types i32 and i64 don't match",
+            ]
+    );
+}
+
+/// makes sure functions and calls only reference attribute groups that are defined
+#[test]
+fn attribute_groups() {
+    let module = ModuleParser::new()
+        .parse(
+            r#"declare void @g() #1
+
+define void @f() #0 {
+    call void @g() #2
+    call void @g() #0
+    ret void
+}
+
+attributes #0 = { nounwind }
+"#,
+        )
+        .unwrap();
+
+    let errors = verify_module(&module).unwrap_err();
+
+    assert!(errors.iter().map(|error| error.to_string()).collect::<Vec<_>>() == ["@g: use of undefined attribute group #1", "@f, block %0, instruction 0: use of undefined attribute group #2"]);
+}
+
+/// makes sure every numbered metadata node that's referenced is defined, and that values used as metadata are checked
+#[test]
+fn metadata() {
+    let module = ModuleParser::new()
+        .parse(
+            r#"@x = global i32 0, !dbg !3
+
+declare void @llvm.dbg.value(metadata, metadata, metadata)
+
+define void @f(i32 %a) !dbg !0 {
+    call void @llvm.dbg.value(metadata i32 %b, metadata !1, metadata !DIExpression())
+    ret void, !dbg !2
+}
+
+!llvm.dbg.cu = !{!4}
+
+!0 = distinct !DISubprogram(name: "f", unit: !4)
+!1 = !DILocalVariable(name: "a", scope: !0)
+!2 = !DILocation(line: 1, scope: !{!5})
+"#,
+        )
+        .unwrap();
+
+    let errors = verify_module(&module).unwrap_err();
+
+    assert!(
+        errors.iter().map(|error| error.to_string()).collect::<Vec<_>>()
+            == [
+                "@x: use of undefined metadata !3",
+                "@f, block %0, instruction 0: use of undefined value %b",
+                "!llvm.dbg.cu: use of undefined metadata !4",
+                "!0: use of undefined metadata !4",
+                "!2: use of undefined metadata !5",
+            ]
+    );
+}
+
+/// makes sure operand bundles that can only be given once aren't repeated, and that funclet bundles have a single token
+#[test]
+fn operand_bundles() {
+    let module = ModuleParser::new()
+        .parse(
+            r#"declare void @g()
+
+define void @f() {
+    call void @g() [ "deopt"(i32 1), "deopt"(i32 2), "align"(ptr null, i64 8), "align"(ptr null, i64 8) ]
+    call void @g() [ "funclet"(i32 1) ]
+    call void @g() [ "funclet"() ]
+    ret void
+}
+"#,
+        )
+        .unwrap();
+
+    let errors = verify_module(&module).unwrap_err();
+
+    assert!(
+        errors.iter().map(|error| error.to_string()).collect::<Vec<_>>()
+            == [
+                "@f, block %0, instruction 0: multiple deopt operand bundles",
+                "@f, block %0, instruction 1: funclet operand bundle input has type i32, but token is required",
+                "@f, block %0, instruction 2: funclet operand bundles need exactly one input, got 0",
+            ]
+    );
+}
+
+/// makes sure globals are used as pointers in the address space they're defined in, including when they're called
+#[test]
+fn address_spaces() {
+    let module = ModuleParser::new()
+        .parse(
+            r#"@g = addrspace(1) global i32 0
+
+declare i32 @h(i32) addrspace(1)
+
+define void @f(ptr %p) {
+    %1 = call addrspace(1) i32 @h(i32 1)
+    %2 = call i32 @h(i32 1)
+    %3 = call i32 %p(i32 1)
+    %4 = load i32, ptr addrspace(1) @g
+    %5 = load i32, ptr @g
+    ret void
+}
+"#,
+        )
+        .unwrap();
+
+    let errors = verify_module(&module).unwrap_err();
+
+    assert!(
+        errors.iter().map(|error| error.to_string()).collect::<Vec<_>>()
+            == [
+                "@f, block %0, instruction 1: global @h is defined with type ptr addrspace(1) but used as ptr",
+                "@f, block %0, instruction 4: global @g is defined with type ptr addrspace(1) but used as ptr",
+            ]
+    );
+}